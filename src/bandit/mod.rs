@@ -0,0 +1,145 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::config::{BotConfig, TemplateStrategy};
+use crate::state::{ArmStats, TemplateArms};
+use crate::templates::template_id;
+
+/// Picks the category and template for each scheduled post
+#[derive(Debug, Clone)]
+pub struct TemplateSelector {
+    strategy: TemplateStrategy,
+    exploration: f64,
+    arms: TemplateArms,
+}
+
+impl TemplateSelector {
+    pub fn new(strategy: TemplateStrategy, exploration: f64, arms: TemplateArms) -> Self {
+        Self {
+            strategy,
+            exploration,
+            arms,
+        }
+    }
+
+    pub fn from_config(config: &BotConfig, arms: TemplateArms) -> Self {
+        Self::new(config.template_strategy, config.template_exploration, arms)
+    }
+
+    /// Index into `categories` of the one to post from. A category counts
+    /// as one arm holding the stats of all its templates.
+    pub fn choose_category(&self, categories: &[&str]) -> usize {
+        let stats: Vec<ArmStats> = categories
+            .iter()
+            .map(|category| {
+                let templates = self
+                    .arms
+                    .get(*category)
+                    .into_iter()
+                    .flat_map(|t| t.values());
+                templates.fold(ArmStats::default(), |total, arm| ArmStats {
+                    pulls: total.pulls + arm.pulls,
+                    reward: total.reward + arm.reward,
+                })
+            })
+            .collect();
+        self.choose(&stats)
+    }
+
+    /// Index into `templates`, the texts in `category`, of the one to use
+    pub fn choose_template(&self, category: &str, templates: &[&str]) -> usize {
+        let arms = self.arms.get(category);
+        let stats: Vec<ArmStats> = templates
+            .iter()
+            .map(|text| {
+                arms.and_then(|a| a.get(&template_id(text)))
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect();
+        self.choose(&stats)
+    }
+
+    /// `stats` must not be empty
+    fn choose(&self, stats: &[ArmStats]) -> usize {
+        let mut rng = rand::thread_rng();
+        match self.strategy {
+            TemplateStrategy::Uniform => rng.gen_range(0..stats.len()),
+            TemplateStrategy::Ucb1 => ucb1(stats, self.exploration, &mut rng),
+        }
+    }
+}
+
+/// UCB1: try every arm once, then take the best mean reward plus a bonus
+/// that shrinks the more often an arm has been tried
+fn ucb1(stats: &[ArmStats], exploration: f64, rng: &mut impl Rng) -> usize {
+    let untried: Vec<usize> = (0..stats.len()).filter(|&i| stats[i].pulls == 0).collect();
+    if let Some(&i) = untried.choose(rng) {
+        return i;
+    }
+
+    let ln_total = (stats.iter().map(|s| s.pulls).sum::<u32>() as f64).ln();
+    let score = |arm: &ArmStats| arm.mean() + exploration * (ln_total / arm.pulls as f64).sqrt();
+    (0..stats.len())
+        .max_by(|&a, &b| score(&stats[a]).total_cmp(&score(&stats[b])))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arm(pulls: u32, mean: f64) -> ArmStats {
+        ArmStats {
+            pulls,
+            reward: mean * pulls as f64,
+        }
+    }
+
+    #[test]
+    fn test_ucb1_tries_every_arm_first() {
+        let mut rng = rand::thread_rng();
+        assert_eq!(ucb1(&[arm(5, 0.9), arm(0, 0.0)], 1.0, &mut rng), 1);
+    }
+
+    #[test]
+    fn test_exploration_favors_rarely_tried_arms() {
+        let mut rng = rand::thread_rng();
+        let stats = [arm(100, 0.6), arm(2, 0.4)];
+        assert_eq!(ucb1(&stats, 0.0, &mut rng), 0);
+        assert_eq!(ucb1(&stats, 1.0, &mut rng), 1);
+    }
+
+    #[test]
+    fn test_categories_pool_their_templates() {
+        let mut arms = TemplateArms::new();
+        arms.entry("meme".to_string())
+            .or_default()
+            .insert(template_id("lol"), arm(6, 0.1));
+        let ai = arms.entry("ai".to_string()).or_default();
+        ai.insert(template_id("agents"), arm(3, 0.2));
+        ai.insert(template_id("swarms"), arm(3, 0.8));
+        let selector = TemplateSelector::new(TemplateStrategy::Ucb1, 0.0, arms);
+
+        assert_eq!(selector.choose_category(&["meme", "ai"]), 1);
+        assert_eq!(selector.choose_template("ai", &["agents", "swarms"]), 1);
+        // Stats follow the text, not the position
+        assert_eq!(selector.choose_template("ai", &["swarms", "agents"]), 0);
+        // Templates without stats yet get tried before the best known one
+        assert_eq!(selector.choose_template("ai", &["agents", "swarms", "markets"]), 2);
+    }
+
+    #[test]
+    fn test_uniform_ignores_stats() {
+        let mut arms = TemplateArms::new();
+        arms.entry("ai".to_string())
+            .or_default()
+            .insert(template_id("agents"), arm(50, 1.0));
+        let selector = TemplateSelector::new(TemplateStrategy::Uniform, 0.0, arms);
+
+        let picks: Vec<usize> = (0..200)
+            .map(|_| selector.choose_template("ai", &["agents", "swarms"]))
+            .collect();
+        assert!(picks.contains(&0) && picks.contains(&1));
+    }
+}
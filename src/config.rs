@@ -10,14 +10,134 @@ pub struct BotConfig {
     pub enable_meme: bool,
     pub enable_ai: bool,
     pub enable_agentropic: bool,
+    pub enable_polls: bool,
+    /// Directory holding pattern diagrams to attach to posts
+    pub media_dir: Option<String>,
     // Reply settings
     pub enable_replies: bool,
+    pub mention_source: MentionSource,
+    /// Where the webhook receiver listens when `mention_source` is `Webhook`
+    pub webhook_addr: String,
     pub mention_poll_seconds: u64,
+    pub mention_poll_max_seconds: u64,
+    pub monthly_read_budget: u32,
+    pub mention_fetch_cap: u32,
+    pub mention_backfill_cap: u32,
+    /// Pause after each mention reply
+    pub reply_spacing_seconds: u64,
+    /// Expected ID of the bot account, checked alongside `bot_username` at startup
     pub twitter_user_id: Option<String>,
+    // Direct messages
+    pub enable_dms: bool,
+    pub dm_poll_seconds: u64,
+    pub max_dm_replies_per_day: u32,
+    // Follower lifecycle
+    /// DM each new follower a short welcome
+    pub welcome_dms: bool,
+    /// Follow back new followers that pass the rules below
+    pub follow_back: bool,
+    /// Bio must mention one of these (lowercase); empty accepts any bio
+    pub follow_back_keywords: Vec<String>,
+    pub follow_back_min_account_days: u32,
+    /// Welcome DMs and follows per UTC day, combined
+    pub max_follower_actions_per_day: u32,
+    /// Most recent followers compared on each check
+    pub follower_fetch_cap: u32,
+    // Search engagement
+    /// Recent-search queries to engage with; empty disables the job
+    pub search_queries: Vec<String>,
+    /// Likes, quotes and replies the search job may make per UTC day
+    pub search_daily_budget: u32,
+    // Post metrics
+    /// Collect likes, reposts, replies and quotes for recent scheduled posts.
+    /// Off by default: the lookups share the mention polling read budget.
+    pub collect_metrics: bool,
+    // Template selection
+    pub template_strategy: TemplateStrategy,
+    /// UCB1 exploration weight; 0 always picks the best-known template
+    pub template_exploration: f64,
+    /// Stand-in for api.x.com and upload.twitter.com, e.g. a local mock server
+    pub api_base_url: Option<String>,
+    /// Cassette file to record every API exchange to, secrets redacted
+    pub record_cassette: Option<String>,
+    /// Cassette file to serve every API exchange from instead of the network
+    pub replay_cassette: Option<String>,
+    // Authentication
+    pub auth_mode: AuthMode,
+    pub oauth2_client_id: Option<String>,
+    /// Only set for confidential OAuth 2.0 clients
+    pub oauth2_client_secret: Option<String>,
+    pub oauth2_redirect_uri: String,
+    /// Where the `authorize` command saves tokens and refreshes rewrite them
+    pub oauth2_token_path: String,
+    /// App-only bearer token for public reads
+    pub bearer_token: Option<String>,
+    /// Fetch the app-only token with the consumer key and secret when none is set
+    pub fetch_bearer_token: bool,
+    // State persistence
+    pub state_backend: StateBackend,
+    pub state_path: String,
+    // Rate limiting
+    pub rate_limit_max_wait_seconds: u64,
+    pub reserved_post_slots: u32,
+    // Monthly write quota (posts + replies)
+    pub monthly_post_budget: u32,
+    pub quota_priority: QuotaPriority,
 }
 
 impl BotConfig {
     pub fn from_env() -> Result<Self> {
+        let state_backend = match env::var("STATE_BACKEND")
+            .unwrap_or_else(|_| "json".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "json" => StateBackend::Json,
+            "sqlite" => StateBackend::Sqlite,
+            other => anyhow::bail!("Unknown STATE_BACKEND '{}' (expected json or sqlite)", other),
+        };
+
+        let auth_mode = match env::var("X_AUTH_MODE")
+            .unwrap_or_else(|_| "oauth1".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "oauth1" => AuthMode::OAuth1,
+            "oauth2" => AuthMode::OAuth2,
+            other => anyhow::bail!("Unknown X_AUTH_MODE '{}' (expected oauth1 or oauth2)", other),
+        };
+
+        let mention_source = match env::var("MENTION_SOURCE")
+            .unwrap_or_else(|_| "poll".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "poll" => MentionSource::Poll,
+            "stream" => MentionSource::Stream,
+            "webhook" => MentionSource::Webhook,
+            other => anyhow::bail!("Unknown MENTION_SOURCE '{}' (expected poll, stream or webhook)", other),
+        };
+
+        let quota_priority = match env::var("QUOTA_PRIORITY")
+            .unwrap_or_else(|_| "posts".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "posts" => QuotaPriority::Posts,
+            "replies" => QuotaPriority::Replies,
+            other => anyhow::bail!("Unknown QUOTA_PRIORITY '{}' (expected posts or replies)", other),
+        };
+
+        let template_strategy = match env::var("TEMPLATE_STRATEGY")
+            .unwrap_or_else(|_| "ucb1".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "ucb1" => TemplateStrategy::Ucb1,
+            "uniform" => TemplateStrategy::Uniform,
+            other => anyhow::bail!("Unknown TEMPLATE_STRATEGY '{}' (expected ucb1 or uniform)", other),
+        };
+
         Ok(Self {
             bot_username: env::var("BOT_USERNAME")
                 .unwrap_or_else(|_| "agentropic".to_string()),
@@ -39,13 +159,108 @@ impl BotConfig {
             enable_agentropic: env::var("ENABLE_AGENTROPIC_CONTENT")
                 .unwrap_or_else(|_| "true".to_string())
                 .to_lowercase() == "true",
+            enable_polls: env::var("ENABLE_POLL_CONTENT")
+                .unwrap_or_else(|_| "false".to_string())
+                .to_lowercase() == "true",
+            media_dir: env::var("MEDIA_DIR").ok(),
             enable_replies: env::var("ENABLE_REPLIES")
                 .unwrap_or_else(|_| "true".to_string())
                 .to_lowercase() == "true",
+            mention_source,
+            webhook_addr: env::var("WEBHOOK_ADDR")
+                .unwrap_or_else(|_| "0.0.0.0:8080".to_string()),
             mention_poll_seconds: env::var("MENTION_POLL_SECONDS")
                 .unwrap_or_else(|_| "300".to_string())
                 .parse()?,
+            mention_poll_max_seconds: env::var("MENTION_POLL_MAX_SECONDS")
+                .unwrap_or_else(|_| "3600".to_string())
+                .parse()?,
+            monthly_read_budget: env::var("MONTHLY_READ_BUDGET")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()?,
+            mention_fetch_cap: env::var("MENTION_FETCH_CAP")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()?,
+            mention_backfill_cap: env::var("MENTION_BACKFILL_CAP")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()?,
+            reply_spacing_seconds: env::var("REPLY_SPACING_SECONDS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()?,
             twitter_user_id: env::var("TWITTER_USER_ID").ok(),
+            enable_dms: env::var("ENABLE_DMS")
+                .unwrap_or_else(|_| "false".to_string())
+                .to_lowercase() == "true",
+            dm_poll_seconds: env::var("DM_POLL_SECONDS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()?,
+            max_dm_replies_per_day: env::var("MAX_DM_REPLIES_PER_DAY")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()?,
+            welcome_dms: env::var("WELCOME_DMS")
+                .unwrap_or_else(|_| "false".to_string())
+                .to_lowercase() == "true",
+            follow_back: env::var("FOLLOW_BACK")
+                .unwrap_or_else(|_| "false".to_string())
+                .to_lowercase() == "true",
+            follow_back_keywords: env::var("FOLLOW_BACK_KEYWORDS")
+                .map(|keywords| {
+                    keywords
+                        .split(',')
+                        .map(|k| k.trim().to_lowercase())
+                        .filter(|k| !k.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            follow_back_min_account_days: env::var("FOLLOW_BACK_MIN_ACCOUNT_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            max_follower_actions_per_day: env::var("MAX_FOLLOWER_ACTIONS_PER_DAY")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()?,
+            follower_fetch_cap: env::var("FOLLOWER_FETCH_CAP")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()?,
+            search_queries: env::var("SEARCH_QUERIES")
+                .map(|queries| parse_queries(&queries))
+                .unwrap_or_default(),
+            search_daily_budget: env::var("SEARCH_DAILY_BUDGET")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()?,
+            collect_metrics: env::var("ENABLE_METRICS")
+                .unwrap_or_else(|_| "false".to_string())
+                .to_lowercase() == "true",
+            template_strategy,
+            template_exploration: env::var("TEMPLATE_EXPLORATION")
+                .unwrap_or_else(|_| "1.0".to_string())
+                .parse()?,
+            api_base_url: env::var("X_API_BASE_URL").ok(),
+            record_cassette: env::var("X_RECORD_CASSETTE").ok(),
+            replay_cassette: env::var("X_REPLAY_CASSETTE").ok(),
+            auth_mode,
+            oauth2_client_id: env::var("X_OAUTH2_CLIENT_ID").ok(),
+            oauth2_client_secret: env::var("X_OAUTH2_CLIENT_SECRET").ok(),
+            oauth2_redirect_uri: env::var("X_OAUTH2_REDIRECT_URI")
+                .unwrap_or_else(|_| "http://127.0.0.1:8765/callback".to_string()),
+            oauth2_token_path: env::var("X_OAUTH2_TOKEN_PATH")
+                .unwrap_or_else(|_| "oauth2_tokens.json".to_string()),
+            bearer_token: env::var("X_BEARER_TOKEN").ok(),
+            fetch_bearer_token: env::var("X_FETCH_BEARER_TOKEN")
+                .unwrap_or_else(|_| "false".to_string())
+                .to_lowercase() == "true",
+            state_path: env::var("STATE_PATH")
+                .unwrap_or_else(|_| state_backend.default_path().to_string()),
+            state_backend,
+            rate_limit_max_wait_seconds: env::var("RATE_LIMIT_MAX_WAIT_SECONDS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()?,
+            reserved_post_slots: env::var("RESERVED_POST_SLOTS")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()?,
+            monthly_post_budget: env::var("MONTHLY_POST_BUDGET")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()?,
+            quota_priority,
         })
     }
 
@@ -53,10 +268,6 @@ impl BotConfig {
         format!("0 0 */{} * * *", self.post_interval_hours)
     }
 
-    pub fn get_mention_cron(&self) -> String {
-        format!("0 */{} * * * *", self.mention_poll_seconds / 60)
-    }
-
     pub fn validate(&self) -> Result<()> {
         if self.post_interval_hours == 0 {
             anyhow::bail!("POST_INTERVAL_HOURS must be greater than 0");
@@ -70,10 +281,73 @@ impl BotConfig {
             anyhow::bail!("At least one content type must be enabled");
         }
 
+        if self.monthly_post_budget == 0 {
+            anyhow::bail!("MONTHLY_POST_BUDGET must be greater than 0");
+        }
+
         if self.enable_replies && self.mention_poll_seconds < 60 {
             anyhow::bail!("MENTION_POLL_SECONDS must be at least 60");
         }
 
+        if self.enable_replies && self.mention_poll_max_seconds < self.mention_poll_seconds {
+            anyhow::bail!("MENTION_POLL_MAX_SECONDS must be at least MENTION_POLL_SECONDS");
+        }
+
+        if self.enable_replies && self.monthly_read_budget == 0 {
+            anyhow::bail!("MONTHLY_READ_BUDGET must be greater than 0");
+        }
+
+        if self.auth_mode == AuthMode::OAuth2 && self.oauth2_client_id.is_none() {
+            anyhow::bail!("X_OAUTH2_CLIENT_ID must be set when X_AUTH_MODE is oauth2");
+        }
+
+        if self.enable_replies
+            && self.mention_source == MentionSource::Stream
+            && self.bearer_token.is_none()
+            && !self.fetch_bearer_token
+        {
+            anyhow::bail!("MENTION_SOURCE=stream needs X_BEARER_TOKEN or X_FETCH_BEARER_TOKEN=true");
+        }
+
+        // A cassette buffers each response whole, and the stream never ends
+        if self.enable_replies && self.mention_source == MentionSource::Stream && self.record_cassette.is_some() {
+            anyhow::bail!("X_RECORD_CASSETTE can't record MENTION_SOURCE=stream; use poll or webhook");
+        }
+
+        if self.record_cassette.is_some() && self.replay_cassette.is_some() {
+            anyhow::bail!("Set X_RECORD_CASSETTE or X_REPLAY_CASSETTE, not both");
+        }
+
+        if self.enable_replies && (self.mention_fetch_cap == 0 || self.mention_backfill_cap == 0) {
+            anyhow::bail!("MENTION_FETCH_CAP and MENTION_BACKFILL_CAP must be greater than 0");
+        }
+
+        if self.enable_dms && self.dm_poll_seconds < 60 {
+            anyhow::bail!("DM_POLL_SECONDS must be at least 60");
+        }
+
+        if self.enable_dms && self.max_dm_replies_per_day == 0 {
+            anyhow::bail!("MAX_DM_REPLIES_PER_DAY must be greater than 0");
+        }
+
+        if (self.welcome_dms || self.follow_back)
+            && (self.max_follower_actions_per_day == 0 || self.follower_fetch_cap == 0)
+        {
+            anyhow::bail!("MAX_FOLLOWER_ACTIONS_PER_DAY and FOLLOWER_FETCH_CAP must be greater than 0");
+        }
+
+        if !self.search_queries.is_empty() && self.search_daily_budget == 0 {
+            anyhow::bail!("SEARCH_DAILY_BUDGET must be greater than 0 when SEARCH_QUERIES is set");
+        }
+
+        if self.template_exploration < 0.0 || !self.template_exploration.is_finite() {
+            anyhow::bail!("TEMPLATE_EXPLORATION must be a number of at least 0");
+        }
+
+        if let Some(query) = self.search_queries.iter().find(|q| q.len() > MAX_QUERY_LEN) {
+            anyhow::bail!("Search query is over {} characters: {}", MAX_QUERY_LEN, query);
+        }
+
         Ok(())
     }
 
@@ -97,6 +371,75 @@ impl BotConfig {
     }
 }
 
+/// Recent search rejects longer queries on the basic tier
+const MAX_QUERY_LEN: usize = 512;
+
+/// Split `SEARCH_QUERIES` on `;`, since queries themselves contain commas and quotes
+fn parse_queries(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Full config with defaults, for tests
+#[cfg(test)]
+pub fn test_config() -> BotConfig {
+    BotConfig {
+        bot_username: "test".to_string(),
+        post_interval_hours: 6,
+        max_posts_per_day: 4,
+        enable_crypto: true,
+        enable_meme: true,
+        enable_ai: true,
+        enable_agentropic: true,
+        enable_polls: false,
+        media_dir: None,
+        enable_replies: false,
+        mention_source: MentionSource::Poll,
+        webhook_addr: "127.0.0.1:0".to_string(),
+        mention_poll_seconds: 300,
+        mention_poll_max_seconds: 3600,
+        monthly_read_budget: 100,
+        mention_fetch_cap: 50,
+        mention_backfill_cap: 200,
+        reply_spacing_seconds: 0,
+        twitter_user_id: None,
+        enable_dms: false,
+        dm_poll_seconds: 900,
+        max_dm_replies_per_day: 20,
+        welcome_dms: false,
+        follow_back: false,
+        follow_back_keywords: Vec::new(),
+        follow_back_min_account_days: 30,
+        max_follower_actions_per_day: 20,
+        follower_fetch_cap: 1000,
+        search_queries: Vec::new(),
+        search_daily_budget: 10,
+        collect_metrics: true,
+        template_strategy: TemplateStrategy::Ucb1,
+        template_exploration: 1.0,
+        api_base_url: None,
+        record_cassette: None,
+        replay_cassette: None,
+        auth_mode: AuthMode::OAuth1,
+        oauth2_client_id: None,
+        oauth2_client_secret: None,
+        oauth2_redirect_uri: "http://127.0.0.1:8765/callback".to_string(),
+        oauth2_token_path: "oauth2_tokens.json".to_string(),
+        bearer_token: None,
+        fetch_bearer_token: false,
+        state_backend: StateBackend::Json,
+        state_path: "bot_state.json".to_string(),
+        rate_limit_max_wait_seconds: 60,
+        reserved_post_slots: 1,
+        monthly_post_budget: 500,
+        quota_priority: QuotaPriority::Posts,
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ContentCategory {
     AI,
@@ -104,3 +447,65 @@ pub enum ContentCategory {
     Crypto,
     Meme,
 }
+
+impl ContentCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentCategory::AI => "ai",
+            ContentCategory::Agentropic => "agentropic",
+            ContentCategory::Crypto => "crypto",
+            ContentCategory::Meme => "meme",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateBackend {
+    Json,
+    Sqlite,
+}
+
+impl StateBackend {
+    pub fn default_path(&self) -> &'static str {
+        match self {
+            StateBackend::Json => "bot_state.json",
+            StateBackend::Sqlite => "bot_state.db",
+        }
+    }
+}
+
+/// How requests to X are authorized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthMode {
+    /// OAuth 1.0a app and access tokens from `TWITTER_*` env vars
+    OAuth1,
+    /// OAuth 2.0 PKCE tokens saved by the `authorize` command
+    OAuth2,
+}
+
+/// Where new mentions come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MentionSource {
+    /// Poll the mentions timeline on an adaptive interval
+    Poll,
+    /// Hold open a v2 filtered stream with a rule matching `@BOT_USERNAME`
+    Stream,
+    /// Receive Account Activity webhooks
+    Webhook,
+}
+
+/// How scheduled posts pick their category and template
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateStrategy {
+    /// UCB1 bandit learning from collected post metrics
+    Ucb1,
+    /// Every option equally likely
+    Uniform,
+}
+
+/// Which kind of write keeps its share when the monthly budget runs tight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaPriority {
+    Posts,
+    Replies,
+}
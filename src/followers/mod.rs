@@ -0,0 +1,168 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeSet;
+
+use crate::config::BotConfig;
+use crate::generators::contains_word;
+use crate::twitter::Follower;
+
+/// Who gets followed back
+#[derive(Debug, Clone)]
+pub struct FollowBackRules {
+    /// Bio must mention one of these (lowercase) as whole words; empty accepts any bio
+    pub keywords: Vec<String>,
+    pub min_account_age: Duration,
+}
+
+impl FollowBackRules {
+    /// Why `follower` shouldn't be followed back, if they shouldn't
+    pub fn reject_reason(&self, follower: &Follower, now: DateTime<Utc>) -> Option<String> {
+        match follower.created_at {
+            Some(created_at) if now - created_at >= self.min_account_age => {}
+            Some(created_at) => {
+                return Some(format!(
+                    "account is only {} day(s) old",
+                    (now - created_at).num_days()
+                ))
+            }
+            None => return Some("account age unknown".to_string()),
+        }
+
+        let bio = follower.description.to_lowercase();
+        if !self.keywords.is_empty() && !self.keywords.iter().any(|k| contains_word(&bio, k)) {
+            return Some("bio matches no keyword".to_string());
+        }
+        None
+    }
+}
+
+/// What the follower job does for each new follower
+#[derive(Debug, Clone)]
+pub struct FollowerPolicy {
+    pub welcome: bool,
+    pub follow_back: Option<FollowBackRules>,
+    pub daily_cap: u32,
+    pub fetch_cap: u32,
+}
+
+impl FollowerPolicy {
+    pub fn from_config(config: &BotConfig) -> Self {
+        Self {
+            welcome: config.welcome_dms,
+            follow_back: config.follow_back.then(|| FollowBackRules {
+                keywords: config.follow_back_keywords.clone(),
+                min_account_age: Duration::days(config.follow_back_min_account_days.into()),
+            }),
+            daily_cap: config.max_follower_actions_per_day,
+            fetch_cap: config.follower_fetch_cap,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.welcome || self.follow_back.is_some()
+    }
+}
+
+/// Followers in `current` that weren't in the `previous` snapshot, oldest
+/// follow first. `current` comes newest first, as X lists it.
+///
+/// Only followers ahead of the oldest one still in the snapshot count. Past
+/// it sit older followers that slid into the fetched window because someone
+/// newer unfollowed; they aren't new.
+pub fn new_followers<'a>(
+    previous: &BTreeSet<String>,
+    current: &'a [Follower],
+) -> Vec<&'a Follower> {
+    let window = current
+        .iter()
+        .rposition(|f| previous.contains(&f.id))
+        .unwrap_or(current.len());
+    current[..window]
+        .iter()
+        .rev()
+        .filter(|f| !previous.contains(&f.id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn follower(id: &str, bio: &str, age_days: Option<i64>) -> Follower {
+        let mut follower: Follower = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "username": format!("user{}", id),
+            "description": bio,
+        }))
+        .unwrap();
+        follower.created_at = age_days.map(|days| Utc::now() - Duration::days(days));
+        follower
+    }
+
+    #[test]
+    fn test_follow_back_rules() {
+        let rules = FollowBackRules {
+            keywords: vec!["rust".to_string(), "agents".to_string()],
+            min_account_age: Duration::days(30),
+        };
+        let now = Utc::now();
+
+        assert!(rules
+            .reject_reason(&follower("1", "Building AI Agents", Some(400)), now)
+            .is_none());
+        assert!(rules
+            .reject_reason(&follower("2", "Rust dev", Some(3)), now)
+            .is_some());
+        assert!(rules
+            .reject_reason(&follower("3", "crypto moon", Some(400)), now)
+            .is_some());
+        assert!(rules
+            .reject_reason(&follower("4", "rust", None), now)
+            .is_some());
+        assert!(rules
+            .reject_reason(&follower("5", "Trustworthy crypto takes", Some(400)), now)
+            .is_some());
+
+        let any_bio = FollowBackRules {
+            keywords: Vec::new(),
+            ..rules
+        };
+        assert!(any_bio
+            .reject_reason(&follower("3", "crypto moon", Some(400)), now)
+            .is_none());
+    }
+
+    #[test]
+    fn test_new_followers_oldest_first() {
+        let previous: BTreeSet<String> = ["1".to_string(), "2".to_string()].into();
+        let current = vec![
+            follower("4", "", None),
+            follower("3", "", None),
+            follower("2", "", None),
+        ];
+
+        let ids: Vec<&str> = new_followers(&previous, &current)
+            .iter()
+            .map(|f| f.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["3", "4"]);
+    }
+
+    #[test]
+    fn test_unfollow_inside_the_window_is_not_a_new_follower() {
+        // Since the last check "5" followed and "2" unfollowed, so "0", an older
+        // follower, now makes the fetched window
+        let previous: BTreeSet<String> = ["3".to_string(), "2".to_string(), "1".to_string()].into();
+        let current = vec![
+            follower("5", "", None),
+            follower("3", "", None),
+            follower("1", "", None),
+            follower("0", "", None),
+        ];
+
+        let ids: Vec<&str> = new_followers(&previous, &current)
+            .iter()
+            .map(|f| f.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["5"]);
+    }
+}
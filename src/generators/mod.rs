@@ -1,6 +1,9 @@
-use crate::templates::TweetTemplates;
+use crate::bandit::TemplateSelector;
+use crate::templates::{PollTemplate, TweetTemplates};
 use crate::config::{BotConfig, ContentCategory};
-use rand::seq::SliceRandom;
+use crate::state::PostOrigin;
+use crate::thread::split_thread;
+use std::path::{Path, PathBuf};
 use tracing::warn;
 
 /// Maximum tweet length (Twitter limit)
@@ -8,26 +11,47 @@ const MAX_TWEET_LENGTH: usize = 280;
 
 pub struct TweetGenerator;
 
+/// Whether `phrase` appears in `text` as whole words, not as part of longer ones
+pub fn contains_word(text: &str, phrase: &str) -> bool {
+    fn words(s: &str) -> Vec<&str> {
+        s.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect()
+    }
+    let (text, phrase) = (words(text), words(phrase));
+    !phrase.is_empty() && text.windows(phrase.len()).any(|w| w == phrase.as_slice())
+}
+
+/// A scheduled post ready to go out, with the template it came from
+pub enum Post {
+    /// Text, split into thread parts when it runs long
+    Text(Vec<String>, PostOrigin),
+    Poll(PollTemplate, PostOrigin),
+}
+
 impl TweetGenerator {
-    /// Generate a random bull post based on enabled categories
-    pub fn generate(config: &BotConfig) -> String {
+    /// Generate a bull post from one of the enabled categories
+    pub fn generate(config: &BotConfig, selector: &TemplateSelector) -> (String, PostOrigin) {
         let enabled_categories = config.get_enabled_categories();
 
         if enabled_categories.is_empty() {
-            return TweetTemplates::random_bull_tweet();
+            let templates = TweetTemplates::general_bull_templates();
+            let index = selector.choose_template("bull", &templates);
+            return (templates[index].to_string(), PostOrigin::new("bull", templates[index]));
         }
 
-        // Pick random category from enabled ones
-        let category = enabled_categories
-            .choose(&mut rand::thread_rng())
-            .unwrap();
+        let names: Vec<&str> = enabled_categories.iter().map(ContentCategory::as_str).collect();
+        let category = enabled_categories[selector.choose_category(&names)];
+        Self::generate_from(category, selector)
+    }
 
-        match category {
-            ContentCategory::AI => TweetTemplates::random_ai_tweet(),
-            ContentCategory::Agentropic => TweetTemplates::random_agentropic_tweet(),
-            ContentCategory::Crypto => TweetTemplates::random_crypto_tweet(),
-            ContentCategory::Meme => TweetTemplates::random_meme_tweet(),
-        }
+    fn generate_from(category: ContentCategory, selector: &TemplateSelector) -> (String, PostOrigin) {
+        let templates = match category {
+            ContentCategory::AI => TweetTemplates::ai_templates(),
+            ContentCategory::Agentropic => TweetTemplates::agentropic_templates(),
+            ContentCategory::Crypto => TweetTemplates::crypto_ai_templates(),
+            ContentCategory::Meme => TweetTemplates::meme_ai_templates(),
+        };
+        let index = selector.choose_template(category.as_str(), &templates);
+        (templates[index].to_string(), PostOrigin::new(category.as_str(), templates[index]))
     }
 
     /// Add bot signature, but only if the result fits within 280 chars
@@ -47,39 +71,129 @@ impl TweetGenerator {
         }
     }
 
-    /// Generate and prepare tweet for posting
-    pub fn create_tweet(config: &BotConfig) -> String {
-        let base_tweet = Self::generate(config);
-        Self::add_signature(base_tweet, &config.bot_username)
+    /// Diagram to attach: the first one whose pattern the tweet mentions and
+    /// whose image exists in `MEDIA_DIR`. Returns the image path and alt text.
+    pub fn pick_diagram(config: &BotConfig, tweet: &str) -> Option<(PathBuf, &'static str)> {
+        let dir = Path::new(config.media_dir.as_deref()?);
+        TweetTemplates::pattern_diagrams()
+            .into_iter()
+            .filter(|(pattern, _, _)| contains_word(tweet, pattern))
+            .map(|(_, file, alt_text)| (dir.join(file), alt_text))
+            .find(|(path, _)| path.exists())
+    }
+
+    /// Pick the next scheduled post. Polls, when enabled, compete with the
+    /// enabled text categories as a category of their own.
+    pub fn create_post(config: &BotConfig, selector: &TemplateSelector) -> Post {
+        let enabled_categories = config.get_enabled_categories();
+        if !config.enable_polls {
+            let (parts, origin) = Self::create_tweet(config, selector);
+            return Post::Text(parts, origin);
+        }
+
+        let mut names: Vec<&str> = enabled_categories.iter().map(ContentCategory::as_str).collect();
+        names.push("poll");
+        match enabled_categories.get(selector.choose_category(&names)) {
+            Some(&category) => {
+                let (tweet, origin) = Self::generate_from(category, selector);
+                Post::Text(Self::prepare(config, tweet), origin)
+            }
+            None => {
+                let polls = TweetTemplates::poll_templates();
+                let questions: Vec<&str> = polls.iter().map(|p| p.question).collect();
+                let index = selector.choose_template("poll", &questions);
+                Post::Poll(polls[index], PostOrigin::new("poll", questions[index]))
+            }
+        }
+    }
+
+    /// Results summary posted as a reply once a poll closes
+    pub fn poll_summary(question: &str, results: &[(String, u64)]) -> String {
+        let total: u64 = results.iter().map(|(_, votes)| votes).sum();
+        let headline = question.split("\n").next().unwrap_or(question).trim();
+        if total == 0 {
+            return format!("Poll closed: {}\n\nNo votes this time. Ask again soon!", headline);
+        }
+
+        let mut ranked: Vec<&(String, u64)> = results.iter().collect();
+        ranked.sort_by_key(|(_, votes)| std::cmp::Reverse(*votes));
+        let lines: Vec<String> = ranked
+            .iter()
+            .map(|(label, votes)| format!("{}: {}%", label, votes * 100 / total))
+            .collect();
+        format!(
+            "Poll results: {}\n\n{}\n\n{} vote{}. Thanks for voting!",
+            headline,
+            lines.join("\n"),
+            total,
+            if total == 1 { "" } else { "s" }
+        )
+    }
+
+    /// Generate and prepare tweet for posting, split into a numbered thread if it runs long
+    pub fn create_tweet(config: &BotConfig, selector: &TemplateSelector) -> (Vec<String>, PostOrigin) {
+        let (base_tweet, origin) = Self::generate(config, selector);
+        (Self::prepare(config, base_tweet), origin)
+    }
+
+    fn prepare(config: &BotConfig, tweet: String) -> Vec<String> {
+        split_thread(&Self::add_signature(tweet, &config.bot_username))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{test_config, TemplateStrategy};
+    use crate::state::{ArmStats, TemplateArms};
+    use crate::templates::template_id;
 
-    fn test_config() -> BotConfig {
-        BotConfig {
-            bot_username: "test".to_string(),
-            post_interval_hours: 6,
-            max_posts_per_day: 4,
-            enable_crypto: true,
-            enable_meme: true,
-            enable_ai: true,
-            enable_agentropic: true,
-            enable_replies: false,
-            mention_poll_seconds: 300,
-            twitter_user_id: None,
-        }
+    fn uniform() -> TemplateSelector {
+        TemplateSelector::new(TemplateStrategy::Uniform, 1.0, TemplateArms::new())
     }
 
     #[test]
     fn test_generate_produces_content() {
         let config = test_config();
-        let tweet = TweetGenerator::generate(&config);
+        let (tweet, _) = TweetGenerator::generate(&config, &uniform());
         assert!(!tweet.is_empty());
     }
 
+    #[test]
+    fn test_generate_records_template_origin() {
+        let config = BotConfig {
+            enable_crypto: false,
+            enable_meme: false,
+            enable_agentropic: false,
+            ..test_config()
+        };
+        let (tweet, origin) = TweetGenerator::generate(&config, &uniform());
+        assert_eq!(origin.category, "ai");
+        assert_eq!(origin.template, template_id(&tweet));
+    }
+
+    #[test]
+    fn test_create_post_follows_learned_rewards() {
+        let config = BotConfig {
+            enable_crypto: false,
+            enable_meme: false,
+            enable_agentropic: false,
+            enable_polls: true,
+            ..test_config()
+        };
+        let mut arms = TemplateArms::new();
+        let ai = template_id(TweetTemplates::ai_templates()[0]);
+        let poll = template_id(TweetTemplates::poll_templates()[2].question);
+        arms.entry("ai".to_string()).or_default().insert(ai, ArmStats { pulls: 8, reward: 0.8 });
+        arms.entry("poll".to_string()).or_default().insert(poll, ArmStats { pulls: 8, reward: 7.2 });
+        let greedy = TemplateSelector::new(TemplateStrategy::Ucb1, 0.0, arms);
+
+        match TweetGenerator::create_post(&config, &greedy) {
+            Post::Poll(_, origin) => assert_eq!(origin.category, "poll"),
+            Post::Text(..) => panic!("expected the better-performing poll category"),
+        }
+    }
+
     #[test]
     fn test_signature_respects_length() {
         let long_tweet = "a".repeat(270);
@@ -87,10 +201,39 @@ mod tests {
         assert_eq!(result, long_tweet);
     }
 
+    #[test]
+    fn test_no_diagram_without_media_dir() {
+        let config = test_config();
+        assert!(TweetGenerator::pick_diagram(&config, "Swarm coordination in Rust").is_none());
+    }
+
+    #[test]
+    fn test_diagram_patterns_match_whole_words() {
+        assert!(contains_word("The Team pattern: fixed roles.", "Team"));
+        assert!(contains_word("Swarm-style consensus", "Swarm"));
+        assert!(!contains_word("Teams of agents on Steam", "Team"));
+        assert!(contains_word("into multi-agent systems", "multi agent"));
+    }
+
+    #[test]
+    fn test_poll_summary_ranks_options() {
+        let results = vec![
+            ("Swarm".to_string(), 3),
+            ("Hierarchy".to_string(), 6),
+            ("Market".to_string(), 1),
+        ];
+        let summary = TweetGenerator::poll_summary("Which pattern?\n\n#Agentropic", &results);
+        assert!(summary.starts_with("Poll results: Which pattern?"));
+        assert!(summary.find("Hierarchy: 60%").unwrap() < summary.find("Swarm: 30%").unwrap());
+        assert!(summary.contains("10 votes"));
+        assert!(!summary.contains("#Agentropic"));
+    }
+
     #[test]
     fn test_create_tweet_within_limit() {
         let config = test_config();
-        let tweet = TweetGenerator::create_tweet(&config);
-        assert!(tweet.len() <= 280);
+        let (parts, _) = TweetGenerator::create_tweet(&config, &uniform());
+        assert!(!parts.is_empty());
+        assert!(parts.iter().all(|p| p.len() <= 280));
     }
 }
@@ -1,27 +1,47 @@
+mod bandit;
 mod generators;
 mod filters;
+mod followers;
 mod templates;
 mod config;
 mod twitter;
 mod knowledge;
 mod responder;
+mod state;
+mod quota;
+mod poller;
+mod search;
+mod thread;
+mod webhook;
 
 use anyhow::Result;
 use dotenv::dotenv;
 use tokio_cron_scheduler::{JobScheduler, Job};
 use tracing::{info, warn, error};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::Path;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
 use chrono::{Utc, NaiveDate};
 
 use agentropic_cognition::{BeliefBase, ReasoningEngine};
 
-use generators::TweetGenerator;
+use bandit::TemplateSelector;
+use generators::{Post, TweetGenerator};
 use filters::ContentFilter;
-use config::BotConfig;
-use twitter::TwitterClient;
+use config::{AuthMode, BotConfig, MentionSource};
+use twitter::{
+    image_media_type, AppBearer, Cassette, DmEvent, DmEventsResponse, Follower, OAuth2App, OAuth2Session, MentionData, MentionsMeta, MentionsResponse, RateLimiter, SocialClient, TwitterClient,
+    TwitterError, MAX_LOOKUP_IDS, MEDIA_SCOPE,
+};
 use knowledge::build_knowledge_base;
-use responder::{build_reasoning_engine, generate_response};
+use responder::{build_reasoning_engine, generate_response, welcome_message};
+use followers::FollowerPolicy;
+use state::{BotState, EngagementKind, EngagementRecord, FollowerAction, FollowerActionKind, Identity, MentionBacklog, MetricCounts, PendingPoll, PostKind, PostOrigin, PostRecord, StateHandle};
+use templates::PollTemplate;
+use quota::QuotaPlanner;
+use poller::AdaptivePoller;
 
 /// Tracks daily post count and resets each day
 struct PostTracker {
@@ -31,10 +51,12 @@ struct PostTracker {
 }
 
 impl PostTracker {
-    fn new(max_per_day: u32) -> Self {
+    /// Resume today's count from persisted state
+    fn from_state(max_per_day: u32, state: &BotState) -> Self {
+        let now = Utc::now();
         Self {
-            count: 0,
-            day: Utc::now().date_naive(),
+            count: state.posts_on(&now, PostKind::Original),
+            day: now.date_naive(),
             max_per_day,
         }
     }
@@ -53,14 +75,54 @@ impl PostTracker {
     }
 }
 
+/// Handled mention IDs remembered to drop repeats
+const RECENT_MENTIONS: usize = 1000;
+
 /// Tracks the last processed mention ID to avoid duplicates
+#[derive(Default)]
 struct MentionTracker {
     last_seen_id: Option<String>,
+    /// IDs handled by this run, oldest first. Pushed mentions can arrive out
+    /// of order, so the cursor alone can't tell which ones were answered.
+    recent: VecDeque<String>,
+    /// Pause after each reply; none by default
+    reply_spacing: Duration,
 }
 
 impl MentionTracker {
-    fn new() -> Self {
-        Self { last_seen_id: None }
+    /// Resume from the persisted since_id cursor
+    fn from_state(reply_spacing: Duration, state: &BotState) -> Self {
+        Self {
+            last_seen_id: state.since_id.clone(),
+            reply_spacing,
+            ..Default::default()
+        }
+    }
+
+    /// Remember `id` as handled; false if it already was
+    fn first_sighting(&mut self, id: &str) -> bool {
+        if self.recent.iter().any(|seen| seen == id) {
+            return false;
+        }
+        if self.recent.len() == RECENT_MENTIONS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(id.to_string());
+        true
+    }
+
+    /// Let `id` be handled again, after a reply to it failed
+    fn forget(&mut self, id: &str) {
+        self.recent.retain(|seen| seen != id);
+    }
+
+    /// Move the cursor up to `id` and save it; it never moves backwards
+    fn advance(&mut self, state: &StateHandle, id: &str) -> Result<()> {
+        if is_newer(id, self.last_seen_id.as_deref()) {
+            self.last_seen_id = Some(id.to_string());
+            state.update(|s| s.since_id = Some(id.to_string()))?;
+        }
+        Ok(())
     }
 }
 
@@ -88,14 +150,124 @@ async fn main() -> Result<()> {
     info!("  Agentropic Content: {}", config.enable_agentropic);
     info!("  Crypto Content: {}", config.enable_crypto);
     info!("  Meme Content: {}", config.enable_meme);
+    info!("  Polls: {}", config.enable_polls);
+    info!("  Monthly Write Budget: {} ({:?} first)", config.monthly_post_budget, config.quota_priority);
+    info!("  Auth: {:?}", config.auth_mode);
     info!("  Replies Enabled: {}", config.enable_replies);
     if config.enable_replies {
-        info!("  Mention Poll: every {} seconds", config.mention_poll_seconds);
+        info!("  Mention Source: {:?}", config.mention_source);
+        info!(
+            "  Mention Poll: {}-{} seconds (adaptive), {} reads/month",
+            config.mention_poll_seconds, config.mention_poll_max_seconds, config.monthly_read_budget
+        );
+        info!(
+            "  Mention Fetch Cap: {} per poll, {} on startup backfill",
+            config.mention_fetch_cap, config.mention_backfill_cap
+        );
+    }
+    info!("  DMs Enabled: {}", config.enable_dms);
+    if config.enable_dms {
+        info!(
+            "  DM Poll: every {} seconds, up to {} replies/day",
+            config.dm_poll_seconds, config.max_dm_replies_per_day
+        );
+    }
+    if config.welcome_dms || config.follow_back {
+        info!(
+            "  Followers: welcome DMs {}, follow back {} (keywords {:?}, min {} days), {} actions/day",
+            config.welcome_dms,
+            config.follow_back,
+            config.follow_back_keywords,
+            config.follow_back_min_account_days,
+            config.max_follower_actions_per_day
+        );
     }
+    info!("  Post Metrics: {}", config.collect_metrics);
+    info!(
+        "  Template Selection: {:?} (exploration {})",
+        config.template_strategy, config.template_exploration
+    );
+    if !config.search_queries.is_empty() {
+        info!(
+            "  Search Engagement: {} quer(ies), {} per day",
+            config.search_queries.len(),
+            config.search_daily_budget
+        );
+    }
+
+    // OAuth 2.0 consent runs before any client exists; it's how tokens get made
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match Command::parse(&args)? {
+        Some(Command::Authorize) => return authorize(&config).await,
+        command => command,
+    };
 
-    let twitter_client = Arc::new(TwitterClient::new()?);
+    // One limiter shared by the posting and mention jobs
+    let rate_limiter = Arc::new(RateLimiter::new(
+        Duration::from_secs(config.rate_limit_max_wait_seconds),
+        config.reserved_post_slots,
+    ));
+    let twitter_client = match config.auth_mode {
+        AuthMode::OAuth1 => TwitterClient::new()?,
+        AuthMode::OAuth2 => {
+            let session = OAuth2Session::load(oauth2_app(&config)?, &config.oauth2_token_path)?;
+            TwitterClient::with_authenticator(Box::new(session))
+        }
+    };
+    let mut twitter_client = twitter_client.with_rate_limiter(rate_limiter);
+    if let Some(base_url) = &config.api_base_url {
+        info!("Using API base URL {}", base_url);
+        twitter_client = twitter_client.with_base_url(base_url);
+    }
+    if let Some(token) = &config.bearer_token {
+        info!("Using app-only bearer token for public reads");
+        twitter_client = twitter_client.with_app_auth(Box::new(AppBearer::new(token.clone())));
+    } else if config.fetch_bearer_token {
+        let consumer_key = std::env::var("TWITTER_CONSUMER_KEY")
+            .map_err(|_| anyhow::anyhow!("TWITTER_CONSUMER_KEY not set"))?;
+        let consumer_secret = std::env::var("TWITTER_CONSUMER_SECRET")
+            .map_err(|_| anyhow::anyhow!("TWITTER_CONSUMER_SECRET not set"))?;
+        let bearer = twitter_client
+            .fetch_app_bearer(&consumer_key, &consumer_secret)
+            .await?;
+        twitter_client = twitter_client.with_app_auth(Box::new(bearer));
+    }
+    if let Some(path) = &config.record_cassette {
+        info!("Recording API traffic to {}", path);
+        twitter_client = twitter_client.with_cassette(Cassette::record(path)?);
+    }
+    if let Some(path) = &config.replay_cassette {
+        warn!("Replaying API traffic from {}; nothing reaches X", path);
+        twitter_client = twitter_client.with_cassette(Cassette::replay(path)?);
+    }
+    let twitter_client = Arc::new(twitter_client);
     info!("Twitter client initialized");
 
+    let state = Arc::new(StateHandle::open(state::open_store(&config)?)?);
+    state.read(|s| {
+        info!(
+            "State loaded from {} ({:?}): since_id={:?}, {} posts logged",
+            config.state_path,
+            config.state_backend,
+            s.since_id,
+            s.posts.len()
+        );
+    });
+
+    // Bad credentials or the wrong account stop the bot here, not at the first post
+    let identity = verify_identity(twitter_client.as_ref(), &config, &state).await?;
+
+    let quota = Arc::new(QuotaPlanner::new(
+        config.monthly_post_budget,
+        config.quota_priority,
+        config.max_posts_per_day,
+    ));
+
+    // One-off commands, e.g. `agentropic-xbot delete <tweet_id>`, run and exit
+    if let Some(command) = command {
+        return run_command(&twitter_client, &identity.user_id, &state, &quota, command).await;
+    }
+
     // Build the Agentropic brain
     let brain = Arc::new(AgentBrain {
         beliefs: build_knowledge_base(),
@@ -103,8 +275,12 @@ async fn main() -> Result<()> {
     });
     info!("Agent brain loaded: knowledge base + reasoning engine");
 
-    let tracker = Arc::new(Mutex::new(PostTracker::new(config.max_posts_per_day)));
-    let mention_tracker = Arc::new(Mutex::new(MentionTracker::new()));
+    let tracker = Arc::new(Mutex::new(
+        state.read(|s| PostTracker::from_state(config.max_posts_per_day, s)),
+    ));
+    let mention_tracker = Arc::new(Mutex::new(state.read(|s| {
+        MentionTracker::from_state(Duration::from_secs(config.reply_spacing_seconds), s)
+    })));
 
     let scheduler = JobScheduler::new().await?;
 
@@ -115,13 +291,25 @@ async fn main() -> Result<()> {
     let config_clone = config.clone();
     let client_clone = Arc::clone(&twitter_client);
     let tracker_clone = Arc::clone(&tracker);
+    let state_clone = Arc::clone(&state);
+    let quota_clone = Arc::clone(&quota);
 
     let tweet_job = Job::new_async(cron_expr.as_str(), move |_uuid, _lock| {
         let config_inner = config_clone.clone();
         let client_inner = Arc::clone(&client_clone);
         let tracker_inner = Arc::clone(&tracker_clone);
+        let state_inner = Arc::clone(&state_clone);
+        let quota_inner = Arc::clone(&quota_clone);
         Box::pin(async move {
-            if let Err(e) = post_tweet(&client_inner, &config_inner, &tracker_inner).await {
+            if let Err(e) = post_tweet(
+                client_inner.as_ref(),
+                &config_inner,
+                &tracker_inner,
+                &state_inner,
+                &quota_inner,
+            )
+            .await
+            {
                 error!("Failed to post tweet: {}", e);
             }
         })
@@ -129,65 +317,309 @@ async fn main() -> Result<()> {
 
     scheduler.add(tweet_job).await?;
 
+    // --- Poll results job ---
+    if config.enable_polls {
+        let client_poll = Arc::clone(&twitter_client);
+        let state_poll = Arc::clone(&state);
+        let quota_poll = Arc::clone(&quota);
+
+        let poll_job = Job::new_async(POLL_RESULTS_CRON, move |_uuid, _lock| {
+            let client_inner = Arc::clone(&client_poll);
+            let state_inner = Arc::clone(&state_poll);
+            let quota_inner = Arc::clone(&quota_poll);
+            Box::pin(async move {
+                if let Err(e) = summarize_closed_polls(client_inner.as_ref(), &state_inner, &quota_inner).await {
+                    error!("Failed to summarize polls: {}", e);
+                }
+            })
+        })?;
+
+        scheduler.add(poll_job).await?;
+        info!("Poll results check scheduled: {}", POLL_RESULTS_CRON);
+    }
+
+    // --- Post metrics job ---
+    if config.collect_metrics {
+        let client_metrics = Arc::clone(&twitter_client);
+        let state_metrics = Arc::clone(&state);
+        let read_budget = config.monthly_read_budget;
+
+        let metrics_job = Job::new_async(METRICS_CRON, move |_uuid, _lock| {
+            let client_inner = Arc::clone(&client_metrics);
+            let state_inner = Arc::clone(&state_metrics);
+            Box::pin(async move {
+                if let Err(e) = collect_post_metrics(client_inner.as_ref(), &state_inner, read_budget).await {
+                    error!("Failed to collect post metrics: {}", e);
+                }
+            })
+        })?;
+
+        scheduler.add(metrics_job).await?;
+        info!("Post metrics collection scheduled: {}", METRICS_CRON);
+    }
+
+    // --- Search engagement job ---
+    if !config.search_queries.is_empty() {
+        let client_search = Arc::clone(&twitter_client);
+        let brain_search = Arc::clone(&brain);
+        let state_search = Arc::clone(&state);
+        let quota_search = Arc::clone(&quota);
+        let config_search = config.clone();
+        let identity_search = identity.clone();
+
+        let search_job = Job::new_async(SEARCH_CRON, move |_uuid, _lock| {
+            let client_inner = Arc::clone(&client_search);
+            let brain_inner = Arc::clone(&brain_search);
+            let state_inner = Arc::clone(&state_search);
+            let quota_inner = Arc::clone(&quota_search);
+            let config_inner = config_search.clone();
+            let identity_inner = identity_search.clone();
+            Box::pin(async move {
+                if let Err(e) = engage_with_search(
+                    client_inner.as_ref(),
+                    &brain_inner,
+                    &state_inner,
+                    &quota_inner,
+                    &identity_inner,
+                    &config_inner,
+                )
+                .await
+                {
+                    error!("Failed to engage with search results: {}", e);
+                }
+            })
+        })?;
+
+        scheduler.add(search_job).await?;
+        info!("Search engagement scheduled: {}", SEARCH_CRON);
+    }
+
     // --- Mention reply job ---
-    if config.enable_replies {
-        // Resolve user ID
-        let user_id = match &config.twitter_user_id {
-            Some(id) => {
-                info!("Using configured user ID: {}", id);
-                id.clone()
-            }
-            None => {
-                info!("Looking up user ID for @{}...", config.bot_username);
-                match twitter_client.get_user_id(&config.bot_username).await {
-                    Ok(id) => {
-                        info!("Resolved user ID: {}", id);
-                        id
+    if config.enable_replies && config.mention_source == MentionSource::Poll {
+        let client_mention = Arc::clone(&twitter_client);
+        let brain_clone = Arc::clone(&brain);
+        let mention_tracker_clone = Arc::clone(&mention_tracker);
+        let state_mention = Arc::clone(&state);
+        let quota_mention = Arc::clone(&quota);
+        let user_id_clone = identity.user_id.clone();
+        let min_interval = Duration::from_secs(config.mention_poll_seconds);
+        let read_budget = config.monthly_read_budget;
+        let mut poller = AdaptivePoller::new(
+            min_interval,
+            Duration::from_secs(config.mention_poll_max_seconds),
+            read_budget,
+        );
+
+        let fetch_cap = config.mention_fetch_cap;
+        let backfill_cap = config.mention_backfill_cap;
+
+        tokio::spawn(async move {
+            // Catch up on anything mentioned while the bot was down before settling into polling
+            let mut backfill = state_mention.read(|s| s.since_id.is_some());
+            let mut delay = min_interval;
+            loop {
+                let cap = if backfill {
+                    info!("Backfilling mentions missed since last run (up to {})...", backfill_cap);
+                    backfill = false;
+                    backfill_cap
+                } else {
+                    tokio::time::sleep(delay).await;
+                    fetch_cap
+                };
+
+                // Each check runs as its own task so a panic ends that check, not polling
+                let check = tokio::spawn({
+                    let client = Arc::clone(&client_mention);
+                    let brain = Arc::clone(&brain_clone);
+                    let tracker = Arc::clone(&mention_tracker_clone);
+                    let state = Arc::clone(&state_mention);
+                    let quota = Arc::clone(&quota_mention);
+                    let user_id = user_id_clone.clone();
+                    async move {
+                        check_and_reply_mentions(
+                            client.as_ref(),
+                            &brain,
+                            &tracker,
+                            &state,
+                            &quota,
+                            &user_id,
+                            cap,
+                        )
+                        .await
+                    }
+                });
+                let found = match check.await {
+                    Ok(Ok(found)) => found,
+                    Ok(Err(e)) => {
+                        error!("Failed to process mentions: {}", e);
+                        0
                     }
                     Err(e) => {
-                        error!("Failed to resolve user ID: {}. Replies disabled.", e);
-                        String::new()
+                        error!("Mention check panicked: {}", e);
+                        0
                     }
-                }
+                };
+
+                let now = Utc::now();
+                delay = state_mention.read(|s| poller.next_delay(found, s, now));
+                let (used, projected) = state_mention.read(|s| {
+                    (s.reads_in_month(&now), poller.projected_reads(s, now, delay))
+                });
+                info!(
+                    "Next mention check in {:?} (reads this month: {}/{}, projected {} by month end)",
+                    delay, used, read_budget, projected
+                );
             }
-        };
+        });
 
-        if !user_id.is_empty() {
-            let mention_cron = config.get_mention_cron();
-            info!("Mention poll cron: {}", mention_cron);
-
-            let client_mention = Arc::clone(&twitter_client);
-            let brain_clone = Arc::clone(&brain);
-            let mention_tracker_clone = Arc::clone(&mention_tracker);
-            let user_id_clone = user_id.clone();
-
-            let mention_job = Job::new_async(mention_cron.as_str(), move |_uuid, _lock| {
-                let client_inner = Arc::clone(&client_mention);
-                let brain_inner = Arc::clone(&brain_clone);
-                let tracker_inner = Arc::clone(&mention_tracker_clone);
-                let uid = user_id_clone.clone();
-                Box::pin(async move {
+        info!("Mention polling active for user ID: {}", identity.user_id);
+    }
+
+    // --- Pushed mentions (filtered stream or webhook) ---
+    if config.enable_replies && config.mention_source != MentionSource::Poll {
+        let (sender, mut receiver) = mpsc::channel(64);
+        if config.mention_source == MentionSource::Stream {
+            let client_stream = Arc::clone(&twitter_client);
+            let username = identity.username.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client_stream.stream_mentions(&username, sender).await {
+                    // Nothing else would notice that replies have stopped
+                    error!("Filtered stream stopped: {}; exiting", e);
+                    std::process::exit(1);
+                }
+            });
+        } else {
+            // X signs CRC challenges and deliveries with the app's consumer
+            // secret whatever the auth mode, so OAuth 2.0 setups need it too
+            let consumer_secret = std::env::var("TWITTER_CONSUMER_SECRET")
+                .map_err(|_| anyhow::anyhow!("TWITTER_CONSUMER_SECRET not set"))?;
+            let listener = tokio::net::TcpListener::bind(&config.webhook_addr).await?;
+            tokio::spawn(async move {
+                if let Err(e) = webhook::serve(listener, consumer_secret, sender).await {
+                    // Same as the stream: replies would stop without a word
+                    error!("Webhook receiver stopped: {}; exiting", e);
+                    std::process::exit(1);
+                }
+            });
+        }
+
+        let client_push = Arc::clone(&twitter_client);
+        let brain_push = Arc::clone(&brain);
+        let mention_tracker_push = Arc::clone(&mention_tracker);
+        let state_push = Arc::clone(&state);
+        let quota_push = Arc::clone(&quota);
+        let user_id = identity.user_id.clone();
+        let backfill_cap = config.mention_backfill_cap;
+
+        tokio::spawn(async move {
+            // Pushes only cover mentions from now on, so catch up on the downtime
+            // first, draining any backlog since no poll will come back for it
+            if state_push.read(|s| s.since_id.is_some()) {
+                info!("Backfilling mentions missed since last run ({} per batch)...", backfill_cap);
+                loop {
                     if let Err(e) = check_and_reply_mentions(
-                        &client_inner,
-                        &brain_inner,
-                        &tracker_inner,
-                        &uid,
+                        client_push.as_ref(),
+                        &brain_push,
+                        &mention_tracker_push,
+                        &state_push,
+                        &quota_push,
+                        &user_id,
+                        backfill_cap,
                     )
                     .await
                     {
-                        error!("Failed to process mentions: {}", e);
+                        error!("Failed to backfill mentions: {}", e);
+                        break;
                     }
-                })
-            })?;
+                    if state_push.read(|s| s.mention_backlog.is_none()) {
+                        break;
+                    }
+                }
+            }
 
-            scheduler.add(mention_job).await?;
-            info!("Mention polling active for user ID: {}", user_id);
-        }
+            while let Some(batch) = receiver.recv().await {
+                if let Err(e) = reply_to_mentions(
+                    client_push.as_ref(),
+                    &brain_push,
+                    &mention_tracker_push,
+                    &state_push,
+                    &quota_push,
+                    &user_id,
+                    &batch,
+                )
+                .await
+                {
+                    error!("Failed to process mentions: {}", e);
+                }
+            }
+        });
+
+        info!("Receiving mentions via {:?} for user ID: {}", config.mention_source, identity.user_id);
+    }
+
+    // --- Follower lifecycle job ---
+    let follower_policy = FollowerPolicy::from_config(&config);
+    if follower_policy.is_enabled() {
+        let client_followers = Arc::clone(&twitter_client);
+        let brain_followers = Arc::clone(&brain);
+        let state_followers = Arc::clone(&state);
+        let user_id = identity.user_id.clone();
+        let read_budget = config.monthly_read_budget;
+
+        let followers_job = Job::new_async(FOLLOWERS_CRON, move |_uuid, _lock| {
+            let client_inner = Arc::clone(&client_followers);
+            let brain_inner = Arc::clone(&brain_followers);
+            let state_inner = Arc::clone(&state_followers);
+            let user_id_inner = user_id.clone();
+            let policy_inner = follower_policy.clone();
+            Box::pin(async move {
+                if let Err(e) = handle_new_followers(
+                    client_inner.as_ref(),
+                    &brain_inner,
+                    &state_inner,
+                    &user_id_inner,
+                    &policy_inner,
+                    read_budget,
+                )
+                .await
+                {
+                    error!("Failed to handle new followers: {}", e);
+                }
+            })
+        })?;
+
+        scheduler.add(followers_job).await?;
+        info!("Follower check scheduled: {}", FOLLOWERS_CRON);
+    }
+
+    // --- Direct message job ---
+    if config.enable_dms {
+        let client_dm = Arc::clone(&twitter_client);
+        let brain_dm = Arc::clone(&brain);
+        let state_dm = Arc::clone(&state);
+        let user_id = identity.user_id.clone();
+        let interval = Duration::from_secs(config.dm_poll_seconds);
+        let daily_cap = config.max_dm_replies_per_day;
+        let read_budget = config.monthly_read_budget;
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) =
+                    check_and_reply_dms(client_dm.as_ref(), &brain_dm, &state_dm, &user_id, daily_cap, read_budget)
+                        .await
+                {
+                    error!("Failed to process DMs: {}", e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        info!("DM polling active every {:?}", interval);
     }
 
     // Post one immediately on startup
     info!("Posting initial tweet...");
-    post_tweet(&twitter_client, &config, &tracker).await?;
+    post_tweet(twitter_client.as_ref(), &config, &tracker, &state, &quota).await?;
 
     // Start scheduler
     scheduler.start().await?;
@@ -199,11 +631,202 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn post_tweet(
+/// Check the credentials against `/2/users/me` and cache whose they are.
+///
+/// Refuses to run as any account but `BOT_USERNAME`. The cached identity is
+/// only trusted when X can't be reached; a rejection is always fatal.
+async fn verify_identity<C: SocialClient>(
+    client: &C,
+    config: &BotConfig,
+    state: &StateHandle,
+) -> Result<Identity> {
+    let user = match client.verify_credentials().await {
+        Ok(user) => user,
+        Err(e) if e.is_retryable() => {
+            let cached = state
+                .read(|s| s.identity.clone())
+                .filter(|i| i.username.eq_ignore_ascii_case(&config.bot_username))
+                .filter(|i| config.twitter_user_id.as_ref().is_none_or(|id| *id == i.user_id))
+                .ok_or_else(|| anyhow::anyhow!("Credential check failed: {}", e))?;
+            warn!(
+                "Credential check failed ({}); using @{} as verified at {}",
+                e, cached.username, cached.verified_at
+            );
+            return Ok(cached);
+        }
+        Err(e) => anyhow::bail!("Credential check failed: {}", e),
+    };
+
+    if !user.username.eq_ignore_ascii_case(&config.bot_username) {
+        anyhow::bail!(
+            "Credentials belong to @{}, not BOT_USERNAME @{}",
+            user.username,
+            config.bot_username
+        );
+    }
+    if let Some(expected) = config.twitter_user_id.as_ref().filter(|id| **id != user.id) {
+        anyhow::bail!(
+            "Credentials belong to user ID {}, not TWITTER_USER_ID {}",
+            user.id,
+            expected
+        );
+    }
+    if user.protected {
+        warn!("@{} is protected; only approved followers will see its posts", user.username);
+    }
+    info!("Authenticated as @{} (ID {})", user.username, user.id);
+
+    let identity = Identity {
+        user_id: user.id,
+        username: user.username,
+        protected: user.protected,
+        verified_at: Utc::now(),
+    };
+    state.update(|s| s.identity = Some(identity.clone()))?;
+    Ok(identity)
+}
+
+const USAGE: &str = "Usage: agentropic-xbot [authorize | like|unlike|retweet|unretweet|delete <tweet_id> | quote <tweet_id> <text>]";
+
+/// One-off command given on the command line instead of running the bot
+#[derive(Debug, PartialEq)]
+enum Command {
+    Authorize,
+    Like(String),
+    Unlike(String),
+    Retweet(String),
+    Unretweet(String),
+    Delete(String),
+    Quote(String, String),
+}
+
+impl Command {
+    /// `None` when no arguments were given; anything unrecognized fails with `USAGE`
+    fn parse(args: &[String]) -> Result<Option<Self>> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let command = match args.as_slice() {
+            [] => return Ok(None),
+            ["authorize"] => Command::Authorize,
+            ["like", tweet_id] => Command::Like(tweet_id.to_string()),
+            ["unlike", tweet_id] => Command::Unlike(tweet_id.to_string()),
+            ["retweet", tweet_id] => Command::Retweet(tweet_id.to_string()),
+            ["unretweet", tweet_id] => Command::Unretweet(tweet_id.to_string()),
+            ["delete", tweet_id] => Command::Delete(tweet_id.to_string()),
+            ["quote", tweet_id, text @ ..] if !text.is_empty() => {
+                Command::Quote(tweet_id.to_string(), text.join(" "))
+            }
+            _ => anyhow::bail!(USAGE),
+        };
+        Ok(Some(command))
+    }
+}
+
+/// OAuth 2.0 app settings from the config, pointed at the stand-in server if one is set
+fn oauth2_app(config: &BotConfig) -> Result<OAuth2App> {
+    let client_id = config
+        .oauth2_client_id
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("X_OAUTH2_CLIENT_ID not set"))?;
+    let mut app = OAuth2App::new(
+        client_id,
+        config.oauth2_client_secret.clone(),
+        config.oauth2_redirect_uri.clone(),
+    );
+    if let Some(base_url) = &config.api_base_url {
+        app = app.with_token_url(&format!("{}/2/oauth2/token", base_url.trim_end_matches('/')));
+    }
+    Ok(app)
+}
+
+/// Walk the account owner through OAuth 2.0 consent and save the resulting tokens
+async fn authorize(config: &BotConfig) -> Result<()> {
+    let tokens = oauth2_app(config)?.authorize(&reqwest::Client::new()).await?;
+    tokens.save(Path::new(&config.oauth2_token_path))?;
+    info!(
+        "OAuth 2.0 tokens saved to {}; set X_AUTH_MODE=oauth2 to use them",
+        config.oauth2_token_path
+    );
+    if !tokens.grants(MEDIA_SCOPE) {
+        warn!("The {} scope wasn't granted, so posts will go out without images", MEDIA_SCOPE);
+    }
+    Ok(())
+}
+
+/// Run a single engagement command against a tweet and exit
+async fn run_command(
     client: &TwitterClient,
+    user_id: &str,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
+    command: Command,
+) -> Result<()> {
+    match command {
+        Command::Authorize => unreachable!("authorize runs before the client is built"),
+        Command::Like(tweet_id) => {
+            let response = client.like(user_id, &tweet_id).await?;
+            info!("Liked {}: {}", tweet_id, response.data.liked);
+        }
+        Command::Unlike(tweet_id) => {
+            let response = client.unlike(user_id, &tweet_id).await?;
+            info!("Unliked {}: {}", tweet_id, !response.data.liked);
+        }
+        Command::Retweet(tweet_id) => {
+            let response = client.retweet(user_id, &tweet_id).await?;
+            info!("Retweeted {}: {}", tweet_id, response.data.retweeted);
+        }
+        Command::Unretweet(tweet_id) => {
+            let response = client.unretweet(user_id, &tweet_id).await?;
+            info!("Unretweeted {}: {}", tweet_id, !response.data.retweeted);
+        }
+        Command::Delete(tweet_id) => {
+            let response = client.delete_tweet(&tweet_id).await?;
+            info!("Deleted {}: {}", tweet_id, response.data.deleted);
+            state.update(|s| s.remove_post(&tweet_id))?;
+        }
+        Command::Quote(tweet_id, text) => {
+            let text = ContentFilter::validate(text)
+                .ok_or_else(|| anyhow::anyhow!("Quote text failed the content filter"))?;
+            if state.read(|s| quota.forecast(s, Utc::now()).remaining()) == 0 {
+                anyhow::bail!("Monthly write budget is exhausted");
+            }
+            let response = client.quote_tweet(&tweet_id, &text).await?;
+            info!("Quoted {} as {}", tweet_id, response.data.id);
+            state.update(|s| {
+                s.record_post(PostRecord {
+                    tweet_id: response.data.id.clone(),
+                    kind: PostKind::Original,
+                    text: text.clone(),
+                    in_reply_to: None,
+                    posted_at: Utc::now(),
+                })
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn post_tweet<C: SocialClient>(
+    client: &C,
     config: &BotConfig,
-    tracker: &Arc<Mutex<PostTracker>>,
+    tracker: &Mutex<PostTracker>,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
 ) -> Result<()> {
+    let forecast = state.read(|s| quota.forecast(s, Utc::now()));
+    info!(
+        "Monthly quota: {}/{} used ({} posts, {} replies), projected {} by month end",
+        forecast.used(),
+        forecast.budget,
+        forecast.used_originals,
+        forecast.used_replies,
+        forecast.projected
+    );
+    if !state.read(|s| quota.allows(PostKind::Original, s, Utc::now())) {
+        warn!("Monthly write budget is too tight for another post, skipping");
+        return Ok(());
+    }
+
     {
         let mut t = tracker.lock().await;
         if !t.try_post() {
@@ -214,33 +837,91 @@ async fn post_tweet(
     }
 
     info!("Generating tweet...");
-    let tweet = TweetGenerator::create_tweet(config);
+    let selector = TemplateSelector::from_config(config, state.read(BotState::template_stats));
+    let (parts, origin) = match TweetGenerator::create_post(config, &selector) {
+        Post::Text(parts, origin) => (parts, origin),
+        Post::Poll(poll, origin) => return post_poll(client, state, &poll, origin).await,
+    };
 
-    let validated_tweet = match ContentFilter::validate(tweet) {
-        Some(t) => t,
+    let validated: Option<Vec<String>> = parts.into_iter().map(ContentFilter::validate).collect();
+    let validated = match validated {
+        Some(parts) => parts,
         None => {
             error!("Tweet failed validation, skipping");
             return Ok(());
         }
     };
 
-    let preview = validated_tweet.chars().take(50).collect::<String>();
-    info!("Tweet preview: {}...", preview);
+    let preview = validated[0].chars().take(50).collect::<String>();
+    if validated.len() > 1 {
+        info!("Thread of {} parts, preview: {}...", validated.len(), preview);
+    } else {
+        info!("Tweet preview: {}...", preview);
+    }
+
+    // Diagrams only ride along with single tweets
+    let media_ids = match TweetGenerator::pick_diagram(config, &validated[0]) {
+        Some((path, alt_text)) if validated.len() == 1 => {
+            attach_image(client, &path, alt_text).await
+        }
+        _ => Vec::new(),
+    };
 
     const MAX_RETRIES: u32 = 3;
+    // Longest we'll wait in-process for a rate-limit window to reset
+    let max_rate_limit_wait = Duration::from_secs(config.rate_limit_max_wait_seconds);
     let mut last_error = None;
+    // Thread parts already posted; a retry resumes after them
+    let mut posted: Vec<String> = Vec::new();
 
     for attempt in 1..=MAX_RETRIES {
-        match client.post_tweet(&validated_tweet).await {
-            Ok(response) => {
-                info!("Tweet posted successfully! ID: {}", response.data.id);
+        let result = if validated.len() == 1 {
+            client
+                .post_tweet(&validated[0], &media_ids)
+                .await
+                .map(|r| vec![r.data.id])
+        } else if posted.is_empty() {
+            client.post_thread(&validated).await
+        } else {
+            client.resume_thread(&validated, None, posted.clone()).await
+        };
+
+        if let Err(TwitterError::ThreadIncomplete { posted: done, .. }) = &result {
+            if let Err(e) = record_thread(state, PostKind::Original, &validated, done, None, posted.len()) {
+                error!("Failed to persist thread parts {}: {}", done.join(", "), e);
+            }
+            posted = done.clone();
+        }
+
+        match result {
+            Ok(ids) => {
+                info!("Tweet posted successfully! ID: {}", ids.join(", "));
+                // The tweet is out either way; a state error mustn't report it as failed
+                if let Err(e) = record_thread(state, PostKind::Original, &validated, &ids, None, posted.len()) {
+                    error!("Failed to persist tweet {}: {}", ids.join(", "), e);
+                }
+                // A thread's metrics are tracked on its first tweet
+                if let Err(e) = state.update(|s| s.track_post(&ids[0], origin, Utc::now())) {
+                    error!("Failed to track metrics for {}: {}", ids[0], e);
+                }
                 return Ok(());
             }
+            Err(e) if !e.is_retryable() => {
+                error!("Tweet rejected, not retrying: {}", e);
+                return Err(e.into());
+            }
             Err(e) => {
                 warn!("Tweet attempt {}/{} failed: {}", attempt, MAX_RETRIES, e);
+                let backoff = match e.retry_after() {
+                    Some(wait) if wait > max_rate_limit_wait => {
+                        warn!("Rate limit resets in {:?}, giving up for now", wait);
+                        return Err(e.into());
+                    }
+                    Some(wait) => wait,
+                    None => Duration::from_secs(2u64.pow(attempt)),
+                };
                 last_error = Some(e);
                 if attempt < MAX_RETRIES {
-                    let backoff = std::time::Duration::from_secs(2u64.pow(attempt));
                     info!("Retrying in {:?}...", backoff);
                     tokio::time::sleep(backoff).await;
                 }
@@ -248,15 +929,486 @@ async fn post_tweet(
         }
     }
 
-    Err(last_error.unwrap())
+    Err(last_error.unwrap().into())
 }
 
-async fn check_and_reply_mentions(
-    client: &TwitterClient,
+async fn post_poll<C: SocialClient>(
+    client: &C,
+    state: &StateHandle,
+    poll: &PollTemplate,
+    origin: PostOrigin,
+) -> Result<()> {
+    let question = match ContentFilter::validate(poll.question.to_string()) {
+        Some(q) => q,
+        None => {
+            error!("Poll failed validation, skipping");
+            return Ok(());
+        }
+    };
+    let options: Vec<String> = poll.options.iter().map(|o| o.to_string()).collect();
+
+    info!("Posting poll: {:?} ({} minutes)", options, poll.duration_minutes);
+    let response = client
+        .post_poll(&question, &options, poll.duration_minutes)
+        .await?;
+    info!("Poll posted successfully! ID: {}", response.data.id);
+
+    let now = Utc::now();
+    state.update(|s| {
+        s.record_post(PostRecord {
+            tweet_id: response.data.id.clone(),
+            kind: PostKind::Original,
+            text: question.clone(),
+            in_reply_to: None,
+            posted_at: now,
+        });
+        s.pending_polls.push(PendingPoll {
+            tweet_id: response.data.id.clone(),
+            question: question.clone(),
+            closes_at: now + chrono::Duration::minutes(poll.duration_minutes as i64),
+        });
+        s.track_post(&response.data.id, origin, now);
+    })
+}
+
+/// Reply to each closed poll with its results
+async fn summarize_closed_polls<C: SocialClient>(
+    client: &C,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
+) -> Result<()> {
+    let closed = state.read(|s| s.closed_polls(&Utc::now()));
+
+    for pending in closed {
+        if !state.read(|s| quota.allows(PostKind::Reply, s, Utc::now())) {
+            warn!("Monthly write budget is too tight for poll summaries, trying later");
+            break;
+        }
+
+        let poll = match client.get_poll(&pending.tweet_id).await {
+            Ok(poll) => poll,
+            Err(e) => {
+                poll_failed(state, &pending, "fetch", &e)?;
+                continue;
+            }
+        };
+        state.update(|s| s.record_read(&Utc::now()))?;
+
+        let Some(poll) = poll else {
+            warn!("Tweet {} has no poll attached, dropping it", pending.tweet_id);
+            state.update(|s| s.remove_poll(&pending.tweet_id))?;
+            continue;
+        };
+        if !poll.is_closed() {
+            info!("Poll {} still open (ends {:?})", pending.tweet_id, poll.end_datetime);
+            continue;
+        }
+
+        let results: Vec<(String, u64)> = poll
+            .options
+            .iter()
+            .map(|o| (o.label.clone(), o.votes))
+            .collect();
+        let summary = match ContentFilter::validate(TweetGenerator::poll_summary(&pending.question, &results)) {
+            Some(text) => text,
+            None => {
+                warn!("Poll summary for {} failed content filter, dropping it", pending.tweet_id);
+                state.update(|s| s.remove_poll(&pending.tweet_id))?;
+                continue;
+            }
+        };
+
+        let response = match client.reply_to_tweet(&pending.tweet_id, &summary, &[]).await {
+            Ok(response) => response,
+            Err(e) => {
+                poll_failed(state, &pending, "summarize", &e)?;
+                continue;
+            }
+        };
+        info!("Poll summary posted! ID: {}", response.data.id);
+        state.update(|s| {
+            s.record_post(PostRecord {
+                tweet_id: response.data.id.clone(),
+                kind: PostKind::Reply,
+                text: summary.clone(),
+                in_reply_to: Some(pending.tweet_id.clone()),
+                posted_at: Utc::now(),
+            });
+            s.remove_poll(&pending.tweet_id);
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Keep a poll for the next check if `e` may clear up, otherwise stop tracking it
+fn poll_failed(state: &StateHandle, pending: &PendingPoll, action: &str, e: &TwitterError) -> Result<()> {
+    if e.is_retryable() {
+        warn!("Failed to {} poll {}: {}, trying later", action, pending.tweet_id, e);
+        return Ok(());
+    }
+    error!("Failed to {} poll {}: {}, dropping it", action, pending.tweet_id, e);
+    state.update(|s| s.remove_poll(&pending.tweet_id))
+}
+
+/// Upload an image for a post; on failure the post goes out as text only
+async fn attach_image<C: SocialClient>(client: &C, path: &Path, alt_text: &str) -> Vec<String> {
+    let Some(media_type) = image_media_type(path) else {
+        warn!("Unsupported image type {}, posting without it", path.display());
+        return Vec::new();
+    };
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Failed to read {}: {}, posting without it", path.display(), e);
+            return Vec::new();
+        }
+    };
+    info!("Attaching {} ({} bytes)", path.display(), bytes.len());
+    match client.upload_media(&bytes, media_type, Some(alt_text)).await {
+        Ok(media_id) => vec![media_id],
+        Err(e) => {
+            warn!("Media upload failed: {}, posting without it", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Log thread parts `ids[from..]` as posts, each replying to the part before it
+fn record_thread(
+    state: &StateHandle,
+    kind: PostKind,
+    parts: &[String],
+    ids: &[String],
+    in_reply_to: Option<&str>,
+    from: usize,
+) -> Result<()> {
+    state.update(|s| {
+        for (i, (id, text)) in ids.iter().zip(parts).enumerate().skip(from) {
+            let parent = match i {
+                0 => in_reply_to.map(str::to_string),
+                _ => Some(ids[i - 1].clone()),
+            };
+            s.record_post(PostRecord {
+                tweet_id: id.clone(),
+                kind,
+                text: text.clone(),
+                in_reply_to: parent,
+                posted_at: Utc::now(),
+            });
+        }
+    })
+}
+
+/// Check for closed polls at five past every hour
+const POLL_RESULTS_CRON: &str = "0 5 * * * *";
+
+/// Collect post metrics once a day
+const METRICS_CRON: &str = "0 45 6 * * *";
+
+/// Fetch public metrics for the scheduled posts still inside the collection
+/// window and store them alongside each post's template and category. Older
+/// posts keep their last counts. Every lookup of up to `MAX_LOOKUP_IDS`
+/// tweets is one read, and lookups stop once the monthly read budget is
+/// spent. Returns how many posts were updated.
+async fn collect_post_metrics<C: SocialClient>(client: &C, state: &StateHandle, read_budget: u32) -> Result<usize> {
+    let due = state.read(|s| s.metrics_due(&Utc::now()));
+    let mut updated = 0;
+
+    for ids in due.chunks(MAX_LOOKUP_IDS) {
+        if read_budget_spent(state, read_budget) {
+            warn!("Monthly read budget is spent, skipping the remaining metrics lookups");
+            break;
+        }
+        let lookup = client.get_tweet_metrics(ids).await?;
+        let now = Utc::now();
+        state.update(|s| {
+            s.record_read(&now);
+            for tweet in &lookup.data {
+                let metrics = &tweet.public_metrics;
+                let counts = MetricCounts {
+                    likes: metrics.like_count,
+                    reposts: metrics.retweet_count,
+                    replies: metrics.reply_count,
+                    quotes: metrics.quote_count,
+                    impressions: metrics.impression_count,
+                };
+                s.record_metrics(&tweet.id, counts, now);
+            }
+        })?;
+        updated += lookup.data.len();
+    }
+
+    // (posts, engagements) per category, over every post measured so far
+    let mut by_category: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    state.read(|s| {
+        for metrics in s.post_metrics.iter().filter(|m| m.collected_at.is_some()) {
+            let entry = by_category.entry(metrics.origin.category.clone()).or_default();
+            entry.0 += 1;
+            entry.1 += metrics.counts.engagements();
+        }
+    });
+    info!("Updated metrics for {} of {} recent post(s)", updated, due.len());
+    for (category, (posts, engagements)) in by_category {
+        info!(
+            "  {}: {} post(s), {:.1} engagements/post",
+            category,
+            posts,
+            engagements as f64 / posts as f64
+        );
+    }
+
+    Ok(updated)
+}
+
+/// Run the search queries at half past every eighth hour
+const SEARCH_CRON: &str = "0 30 */8 * * *";
+
+/// Results fetched per query per run; the API minimum, as every page is a read
+const SEARCH_RESULTS_PER_QUERY: u32 = 10;
+
+/// Whether this month's API reads have used up `budget`
+fn read_budget_spent(state: &StateHandle, budget: u32) -> bool {
+    state.read(|s| s.reads_in_month(&Utc::now()) >= budget)
+}
+
+/// Run the search queries and engage with the tweets that best match our
+/// topics, spending what's left of today's search budget. Returns how many
+/// tweets were engaged with.
+async fn engage_with_search<C: SocialClient>(
+    client: &C,
     brain: &AgentBrain,
-    mention_tracker: &Arc<Mutex<MentionTracker>>,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
+    identity: &Identity,
+    config: &BotConfig,
+) -> Result<usize> {
+    let engaged_today = state.read(|s| s.engagements_on(&Utc::now()));
+    let remaining = config.search_daily_budget.saturating_sub(engaged_today);
+    if remaining == 0 {
+        info!("Search engagement budget spent for today");
+        return Ok(0);
+    }
+
+    let mut results = Vec::new();
+    for query in &config.search_queries {
+        if read_budget_spent(state, config.monthly_read_budget) {
+            warn!("Monthly read budget is spent, skipping the remaining searches");
+            break;
+        }
+        let since_id = state.read(|s| s.search_since_ids.get(query).cloned());
+        let found = match client
+            .search_recent(query, since_id.as_deref(), SEARCH_RESULTS_PER_QUERY)
+            .await
+        {
+            Ok(found) => found,
+            Err(e) => {
+                error!("Search {:?} failed: {}", query, e);
+                continue;
+            }
+        };
+        let newest = found.meta.as_ref().and_then(|m| m.newest_id.clone());
+        state.update(|s| {
+            s.record_read(&Utc::now());
+            if let Some(newest) = newest {
+                s.search_since_ids.insert(query.clone(), newest);
+            }
+        })?;
+        info!("Search {:?} found {} tweet(s)", query, found.data.len());
+        results.extend(found.data);
+    }
+
+    let hits = state.read(|s| search::rank_hits(results, &brain.engine, identity, s));
+    let mut engaged = 0;
+    for (hit, kind) in search::plan(hits, remaining) {
+        info!(
+            "Search hit {} from {} ({}) matched {} with {:.0}% confidence",
+            hit.tweet.id,
+            hit.tweet.author_handle(),
+            hit.tweet.author_context(),
+            hit.topic,
+            hit.confidence * 100.0
+        );
+        let done = match engage(client, brain, state, quota, &identity.user_id, &hit.tweet, kind).await {
+            Ok(done) => done,
+            Err(e) => {
+                error!("Failed to engage with {}: {}", hit.tweet.id, e);
+                continue;
+            }
+        };
+        state.update(|s| {
+            s.record_engagement(EngagementRecord {
+                tweet_id: hit.tweet.id.clone(),
+                kind: done,
+                at: Utc::now(),
+            })
+        })?;
+        engaged += 1;
+    }
+
+    Ok(engaged)
+}
+
+/// Quote, reply to or like a search hit. A quote or reply that can't be
+/// written, or doesn't fit the write budget, becomes a like. Returns what
+/// was actually done.
+async fn engage<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
     user_id: &str,
-) -> Result<()> {
+    tweet: &MentionData,
+    kind: EngagementKind,
+) -> Result<EngagementKind> {
+    if kind != EngagementKind::Like {
+        let post_kind = match kind {
+            EngagementKind::Reply => PostKind::Reply,
+            _ => PostKind::Original,
+        };
+        // A quote has to stand alone, so only single-part answers qualify
+        let parts = generate_response(&tweet.text, &brain.beliefs, &brain.engine)
+            .and_then(|parts| parts.into_iter().map(ContentFilter::validate).collect::<Option<Vec<_>>>())
+            .filter(|parts| kind == EngagementKind::Reply || parts.len() == 1);
+
+        match parts {
+            Some(parts) if state.read(|s| quota.allows(post_kind, s, Utc::now())) => {
+                let result = match kind {
+                    EngagementKind::Quote => client
+                        .quote_tweet(&tweet.id, &parts[0])
+                        .await
+                        .map(|r| vec![r.data.id]),
+                    _ if parts.len() == 1 => client
+                        .reply_to_tweet(&tweet.id, &parts[0], &[])
+                        .await
+                        .map(|r| vec![r.data.id]),
+                    _ => client.reply_with_thread(&tweet.id, &parts).await,
+                };
+                let ids = match result {
+                    Ok(ids) => ids,
+                    Err(TwitterError::ThreadIncomplete { posted, source }) => {
+                        error!(
+                            "Reply thread to {} stopped after {} part(s): {}",
+                            tweet.id,
+                            posted.len(),
+                            source
+                        );
+                        posted
+                    }
+                    Err(e) => return Err(e.into()),
+                };
+                info!("Engaged with {} ({:?}): {}", tweet.id, kind, ids.join(", "));
+                let in_reply_to = (kind == EngagementKind::Reply).then_some(tweet.id.as_str());
+                // The post is out either way; a state error mustn't report it as failed
+                if let Err(e) = record_thread(state, post_kind, &parts, &ids, in_reply_to, 0) {
+                    error!("Failed to persist {:?} {}: {}", kind, ids.join(", "), e);
+                }
+                return Ok(kind);
+            }
+            _ => info!("Liking {} instead of a {:?}", tweet.id, kind),
+        }
+    }
+
+    client.like(user_id, &tweet.id).await?;
+    info!("Liked {}", tweet.id);
+    Ok(EngagementKind::Like)
+}
+
+/// Why a mention should not get a reply, if it shouldn't
+fn skip_reason(mention: &MentionData, own_user_id: &str) -> Option<&'static str> {
+    if mention.author_id == own_user_id {
+        return Some("posted by the bot itself");
+    }
+    if mention.is_retweet() {
+        return Some("retweet");
+    }
+    None
+}
+
+/// Fetch mentions newer than `since_id`, following `next_token` until `cap` have been
+/// collected. Every page counts as one read against the monthly budget.
+///
+/// Pages left over at the cap are saved as a backlog that the next call
+/// resumes from, and the returned `newest_id` is withheld until it drains so
+/// the cursor never passes mentions that haven't been seen.
+async fn fetch_mentions<C: SocialClient>(
+    client: &C,
+    state: &StateHandle,
+    user_id: &str,
+    since_id: Option<&str>,
+    cap: u32,
+) -> Result<MentionsResponse> {
+    let backlog = state.read(|s| s.mention_backlog.clone());
+    let (since_id, first_token) = match &backlog {
+        Some(backlog) => {
+            info!("Resuming older mentions left over from the last check");
+            (backlog.since_id.as_deref(), Some(backlog.next_token.as_str()))
+        }
+        None => (since_id, None),
+    };
+
+    let mut mentions = client.get_mentions(user_id, since_id, first_token, cap.min(100)).await?;
+    state.update(|s| s.record_read(&Utc::now()))?;
+
+    while let Some(token) = mentions.next_token().map(str::to_string) {
+        let fetched = mentions.data.len() as u32;
+        if fetched >= cap {
+            break;
+        }
+        let page = client
+            .get_mentions(user_id, since_id, Some(&token), (cap - fetched).min(100))
+            .await?;
+        state.update(|s| s.record_read(&Utc::now()))?;
+        mentions.extend(page);
+    }
+
+    let newest_id = match &backlog {
+        Some(backlog) => backlog.newest_id.clone(),
+        None => mentions.meta.as_ref().and_then(|m| m.newest_id.clone()),
+    };
+    match mentions.next_token().map(str::to_string) {
+        Some(next_token) => {
+            warn!(
+                "Mention cap ({}) reached with more pages pending; older mentions follow next check",
+                cap
+            );
+            if let Some(meta) = mentions.meta.as_mut() {
+                meta.newest_id = None;
+            }
+            let since_id = since_id.map(str::to_string);
+            state.update(|s| {
+                s.mention_backlog = Some(MentionBacklog {
+                    since_id,
+                    next_token,
+                    newest_id,
+                })
+            })?;
+        }
+        None if backlog.is_some() => {
+            let meta = mentions.meta.get_or_insert(MentionsMeta {
+                newest_id: None,
+                oldest_id: None,
+                result_count: 0,
+                next_token: None,
+            });
+            meta.newest_id = newest_id;
+            state.update(|s| s.mention_backlog = None)?;
+        }
+        None => {}
+    }
+
+    Ok(mentions)
+}
+
+async fn check_and_reply_mentions<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    mention_tracker: &Mutex<MentionTracker>,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
+    user_id: &str,
+    cap: u32,
+) -> Result<usize> {
     let since_id = {
         let tracker = mention_tracker.lock().await;
         tracker.last_seen_id.clone()
@@ -264,67 +1416,1006 @@ async fn check_and_reply_mentions(
 
     info!("Checking mentions (since: {:?})...", since_id);
 
-    let mentions = client
-        .get_mentions(user_id, since_id.as_deref())
-        .await?;
+    let mentions = fetch_mentions(client, state, user_id, since_id.as_deref(), cap).await?;
+    reply_to_mentions(client, brain, mention_tracker, state, quota, user_id, &mentions).await
+}
 
+/// Whether tweet ID `id` is newer than `than`; IDs grow over time
+fn is_newer(id: &str, than: Option<&str>) -> bool {
+    match (id.parse::<u64>(), than.map(str::parse::<u64>)) {
+        (Ok(id), Some(Ok(than))) => id > than,
+        (Ok(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Answer a batch of mentions, oldest first, whether polled or pushed by the
+/// filtered stream or webhook. Pushed mentions can arrive out of order, so
+/// they aren't compared with the cursor; overlapping batches are deduped by
+/// the IDs this run has handled (`MentionTracker::first_sighting`). That set
+/// starts empty, so after a restart only the cursor keeps polls and the
+/// backfill from fetching mentions again.
+///
+/// The cursor moves past each mention once it's handled. A quota stop or a
+/// reply that failed in a way worth retrying leaves it behind the rest, so
+/// they're fetched again next check. A batch whose `newest_id` is withheld
+/// (older pages still pending) doesn't move it at all.
+async fn reply_to_mentions<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    mention_tracker: &Mutex<MentionTracker>,
+    state: &StateHandle,
+    quota: &QuotaPlanner,
+    user_id: &str,
+    mentions: &MentionsResponse,
+) -> Result<usize> {
     let count = mentions.data.len();
     if count == 0 {
         info!("No new mentions");
-        return Ok(());
+        return Ok(0);
     }
-
     info!("Found {} new mention(s)", count);
 
-    // Update last seen ID
-    if let Some(meta) = &mentions.meta {
-        if let Some(newest) = &meta.newest_id {
-            let mut tracker = mention_tracker.lock().await;
-            tracker.last_seen_id = Some(newest.clone());
-            info!("Updated last_seen_id to {}", newest);
+    let newest = mentions.meta.as_ref().and_then(|m| m.newest_id.as_deref());
+    let mut completed = true;
+    // Process each mention (oldest first)
+    for mention in mentions.data.iter().rev() {
+        if !state.read(|s| quota.allows(PostKind::Reply, s, Utc::now())) {
+            warn!("Monthly write budget is too tight for replies, leaving remaining mentions for later");
+            completed = false;
+            break;
+        }
+
+        if !mention_tracker.lock().await.first_sighting(&mention.id) {
+            info!("Skipping mention {}: already handled", mention.id);
+            continue;
+        }
+
+        let spacing = mention_tracker.lock().await.reply_spacing;
+        if !reply_to_mention(client, brain, state, user_id, mention, spacing).await? {
+            mention_tracker.lock().await.forget(&mention.id);
+            completed = false;
+            break;
+        }
+        if newest.is_some() {
+            mention_tracker.lock().await.advance(state, &mention.id)?;
         }
     }
 
-    // Process each mention (oldest first)
-    for mention in mentions.data.iter().rev() {
+    if let Some(newest) = newest.filter(|_| completed) {
+        mention_tracker.lock().await.advance(state, newest)?;
+    }
+
+    Ok(count)
+}
+
+/// Answer one mention. `false` means the reply failed in a way worth retrying.
+async fn reply_to_mention<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    state: &StateHandle,
+    user_id: &str,
+    mention: &MentionData,
+    spacing: Duration,
+) -> Result<bool> {
+    if let Some(reason) = skip_reason(mention, user_id) {
+        info!("Skipping mention {} from {}: {}", mention.id, mention.author_handle(), reason);
+        return Ok(true);
+    }
+
+    info!(
+        "Processing mention {} from {} (created {:?}, lang {:?}, conversation {:?}): \"{}\"",
+        mention.id,
+        mention.author_handle(),
+        mention.created_at,
+        mention.lang,
+        mention.conversation_id,
+        mention.text
+    );
+
+    // Generate response using Agentropic reasoning
+    let Some(parts) = generate_response(&mention.text, &brain.beliefs, &brain.engine) else {
+        warn!("Could not generate response for mention {}", mention.id);
+        return Ok(true);
+    };
+
+    // Validate through content filter
+    let validated: Option<Vec<String>> = parts.into_iter().map(ContentFilter::validate).collect();
+    let Some(validated) = validated else {
+        warn!("Reply failed content filter, skipping mention {}", mention.id);
+        return Ok(true);
+    };
+
+    info!(
+        "Replying to {} ({} part(s)): \"{}\"",
+        mention.id,
+        validated.len(),
+        validated[0].chars().take(50).collect::<String>()
+    );
+
+    let result = if validated.len() == 1 {
+        client
+            .reply_to_tweet(&mention.id, &validated[0], &[])
+            .await
+            .map(|r| vec![r.data.id])
+    } else {
+        client.reply_with_thread(&mention.id, &validated).await
+    };
+    // Rate limit: wait between replies
+    tokio::time::sleep(spacing).await;
+    let ids = match result {
+        Ok(ids) => {
+            info!("Reply posted! ID: {}", ids.join(", "));
+            ids
+        }
+        Err(TwitterError::ThreadIncomplete { posted, source }) => {
+            error!(
+                "Reply thread to {} stopped after {} part(s): {}",
+                mention.id,
+                posted.len(),
+                source
+            );
+            posted
+        }
+        Err(e) if e.is_retryable() => {
+            warn!("Failed to reply to {}: {}, retrying next check", mention.id, e);
+            return Ok(false);
+        }
+        Err(e) => {
+            error!("Failed to reply to {}: {}, giving up on it", mention.id, e);
+            return Ok(true);
+        }
+    };
+    if let Err(e) = record_thread(state, PostKind::Reply, &validated, &ids, Some(&mention.id), 0) {
+        error!("Failed to persist reply {}: {}", ids.join(", "), e);
+    }
+    Ok(true)
+}
+
+/// Check followers once a day; each page of the list is a read
+const FOLLOWERS_CRON: &str = "0 15 9 * * *";
+
+/// The `cap` most recent followers, newest first. Every page counts as one read.
+async fn fetch_followers<C: SocialClient>(
+    client: &C,
+    state: &StateHandle,
+    user_id: &str,
+    cap: u32,
+) -> Result<Vec<Follower>> {
+    let mut page = client.get_followers(user_id, None, cap.min(1000)).await?;
+    state.update(|s| s.record_read(&Utc::now()))?;
+    let mut followers = std::mem::take(&mut page.data);
+
+    while let Some(token) = page.next_token().map(str::to_string) {
+        let fetched = followers.len() as u32;
+        if fetched >= cap {
+            break;
+        }
+        page = client
+            .get_followers(user_id, Some(&token), (cap - fetched).min(1000))
+            .await?;
+        state.update(|s| s.record_read(&Utc::now()))?;
+        followers.append(&mut page.data);
+    }
+
+    Ok(followers)
+}
+
+/// Diff the follower list against the last snapshot, then welcome and
+/// follow back whoever is new, within the daily action cap. Followers the
+/// cap leaves unhandled, or whose action failed and may work on a retry,
+/// stay out of the snapshot so the next run picks them up. Returns how many
+/// new followers were found.
+async fn handle_new_followers<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    state: &StateHandle,
+    user_id: &str,
+    policy: &FollowerPolicy,
+    read_budget: u32,
+) -> Result<usize> {
+    if read_budget_spent(state, read_budget) {
+        warn!("Monthly read budget is spent, skipping the follower check");
+        return Ok(0);
+    }
+    let followers = fetch_followers(client, state, user_id, policy.fetch_cap).await?;
+    let mut snapshot: BTreeSet<String> = followers.iter().map(|f| f.id.clone()).collect();
+
+    let Some(previous) = state.read(|s| s.follower_snapshot.clone()) else {
+        info!("Saved first follower snapshot ({} followers); new followers are handled from the next check", snapshot.len());
+        state.update(|s| s.follower_snapshot = Some(snapshot))?;
+        return Ok(0);
+    };
+
+    let new = followers::new_followers(&previous, &followers);
+    info!("Found {} new follower(s)", new.len());
+
+    for (i, follower) in new.iter().enumerate() {
         info!(
-            "Processing mention {} from user {}: \"{}\"",
-            mention.id, mention.author_id, mention.text
+            "New follower @{} ({} followers, created {:?})",
+            follower.username, follower.public_metrics.followers_count, follower.created_at
         );
 
-        // Generate response using Agentropic reasoning
-        let response = generate_response(&mention.text, &brain.beliefs, &brain.engine);
+        let mut actions = Vec::new();
+        if policy.welcome {
+            actions.push(FollowerActionKind::Welcomed);
+        }
+        if let Some(rules) = &policy.follow_back {
+            match rules.reject_reason(follower, Utc::now()) {
+                Some(reason) => info!("Not following back @{}: {}", follower.username, reason),
+                None => actions.push(FollowerActionKind::FollowedBack),
+            }
+        }
+        actions.retain(|kind| !state.read(|s| s.has_follower_action(&follower.id, *kind)));
+
+        let room = policy
+            .daily_cap
+            .saturating_sub(state.read(|s| s.follower_actions_on(&Utc::now())));
+        if actions.len() as u32 > room {
+            warn!(
+                "Daily follower action cap ({}) reached; {} new follower(s) left for the next check",
+                policy.daily_cap,
+                new.len() - i
+            );
+            for deferred in &new[i..] {
+                snapshot.remove(&deferred.id);
+            }
+            break;
+        }
 
-        match response {
-            Some(reply_text) => {
-                // Validate through content filter
-                let validated = match ContentFilter::validate(reply_text) {
-                    Some(t) => t,
-                    None => {
-                        warn!("Reply failed content filter, skipping mention {}", mention.id);
+        for kind in actions {
+            let result = match kind {
+                FollowerActionKind::Welcomed => {
+                    let Some(text) = ContentFilter::validate(welcome_message(&follower.username, &brain.beliefs))
+                    else {
+                        warn!("Welcome DM for @{} failed content filter", follower.username);
                         continue;
+                    };
+                    client
+                        .send_dm(&follower.id, &text)
+                        .await
+                        .map(|r| format!("sent DM {}", r.data.dm_event_id))
+                }
+                FollowerActionKind::FollowedBack => client.follow(user_id, &follower.id).await.map(|r| {
+                    if r.data.pending_follow {
+                        "follow request pending".to_string()
+                    } else {
+                        format!("following: {}", r.data.following)
                     }
-                };
+                }),
+            };
+            match result {
+                Ok(outcome) => {
+                    info!("{:?} @{}: {}", kind, follower.username, outcome);
+                    state.update(|s| {
+                        s.record_follower_action(FollowerAction {
+                            user_id: follower.id.clone(),
+                            username: follower.username.clone(),
+                            kind,
+                            at: Utc::now(),
+                        })
+                    })?;
+                }
+                Err(e) if e.is_retryable() => {
+                    warn!("Failed {:?} for @{}: {}, retrying next check", kind, follower.username, e);
+                    snapshot.remove(&follower.id);
+                }
+                Err(e) => error!("Failed {:?} for @{}: {}", kind, follower.username, e),
+            }
+        }
+    }
+
+    state.update(|s| s.follower_snapshot = Some(snapshot))?;
+    Ok(new.len())
+}
 
-                info!("Replying to {}: \"{}\"", mention.id, &validated[..validated.len().min(50)]);
+/// DM events fetched per check at most; each page of up to 100 is one read
+const DM_FETCH_CAP: u32 = 100;
 
-                match client.reply_to_tweet(&mention.id, &validated).await {
-                    Ok(response) => {
-                        info!("Reply posted! ID: {}", response.data.id);
-                    }
-                    Err(e) => {
-                        error!("Failed to reply to {}: {}", mention.id, e);
-                    }
+/// Fetch DM events newer than `since_id`, newest first. `/2/dm_events` has no
+/// `since_id`, so pages are followed until one reaches the cursor or `cap`
+/// events have been collected. Every page counts as one read.
+async fn fetch_dms<C: SocialClient>(
+    client: &C,
+    state: &StateHandle,
+    since_id: Option<&str>,
+    cap: u32,
+) -> Result<DmEventsResponse> {
+    let mut events = client.get_dm_events(None, cap.min(100)).await?;
+    state.update(|s| s.record_read(&Utc::now()))?;
+
+    while !events.data.iter().any(|e| !is_newer(&e.id, since_id)) {
+        let Some(token) = events.next_token().map(str::to_string) else {
+            break;
+        };
+        let fetched = events.data.len() as u32;
+        if fetched >= cap {
+            warn!("DM cap ({}) reached with more pages pending; older DMs will be skipped", cap);
+            break;
+        }
+        let page = client.get_dm_events(Some(&token), (cap - fetched).min(100)).await?;
+        state.update(|s| s.record_read(&Utc::now()))?;
+        events.extend(page);
+    }
+
+    events.data.retain(|e| is_newer(&e.id, since_id));
+    Ok(events)
+}
+
+/// DMs older than this are skipped rather than answered late
+const MAX_DM_AGE_DAYS: i64 = 3;
+
+/// Why a DM should not get a reply, if it shouldn't
+fn dm_skip_reason(event: &DmEvent, own_user_id: &str) -> Option<&'static str> {
+    if !event.is_message() {
+        return Some("not a message");
+    }
+    match event.sender_id.as_deref() {
+        None => return Some("no sender"),
+        // Our own replies show up in the same event list
+        Some(sender) if sender == own_user_id => return Some("sent by the bot itself"),
+        Some(_) => {}
+    }
+    if let Some(created_at) = event.created_at {
+        if Utc::now() - created_at > chrono::Duration::days(MAX_DM_AGE_DAYS) {
+            return Some("too old to answer");
+        }
+    }
+    None
+}
+
+/// Answer new DMs, oldest first, with the same reasoning used for mentions.
+/// Replies are capped at `daily_cap` per UTC day and don't touch the tweet
+/// budget. The cursor only moves past a DM once it has been dealt with, so
+/// DMs left over at the cap or after a failed send are retried next check.
+/// Returns how many new DM events were found.
+async fn check_and_reply_dms<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    state: &StateHandle,
+    user_id: &str,
+    daily_cap: u32,
+    read_budget: u32,
+) -> Result<usize> {
+    if read_budget_spent(state, read_budget) {
+        warn!("Monthly read budget is spent, skipping the DM check");
+        return Ok(0);
+    }
+    let since_id = state.read(|s| s.dm_since_id.clone());
+    info!("Checking DMs (since: {:?})...", since_id);
+
+    let events = fetch_dms(client, state, since_id.as_deref(), DM_FETCH_CAP).await?;
+    let count = events.data.len();
+    if count == 0 {
+        info!("No new DMs");
+        return Ok(0);
+    }
+    info!("Found {} new DM event(s)", count);
+
+    for event in events.data.iter().rev() {
+        if let Some(reason) = dm_skip_reason(event, user_id) {
+            info!("Skipping DM {} from {}: {}", event.id, event.sender_handle(), reason);
+        } else if state.read(|s| s.dm_replies_on(&Utc::now())) >= daily_cap {
+            warn!("Daily DM reply cap ({}) reached, leaving the rest for later", daily_cap);
+            break;
+        } else if !reply_to_dm(client, brain, state, event).await? {
+            break;
+        }
+        state.update(|s| s.dm_since_id = Some(event.id.clone()))?;
+    }
+
+    Ok(count)
+}
+
+/// Answer one DM. `false` means the send failed in a way worth retrying.
+async fn reply_to_dm<C: SocialClient>(
+    client: &C,
+    brain: &AgentBrain,
+    state: &StateHandle,
+    event: &DmEvent,
+) -> Result<bool> {
+    info!(
+        "Processing DM {} from {} (conversation {:?}, {} chars)",
+        event.id,
+        event.sender_handle(),
+        event.dm_conversation_id,
+        event.text.chars().count()
+    );
+
+    // A DM has room for the whole answer, so thread parts are joined back up
+    let reply = generate_response(&event.text, &brain.beliefs, &brain.engine)
+        .and_then(|parts| parts.into_iter().map(ContentFilter::validate).collect::<Option<Vec<_>>>())
+        .map(|parts| parts.join("\n\n"));
+    let Some(reply) = reply else {
+        warn!("Could not generate an acceptable reply to DM {}", event.id);
+        return Ok(true);
+    };
+
+    let sender_id = event.sender_id.as_deref().unwrap_or_default();
+    match client.send_dm(sender_id, &reply).await {
+        Ok(response) => {
+            info!("DM reply sent! Event ID: {}", response.data.dm_event_id);
+            state.update(|s| s.record_dm_reply(&Utc::now()))?;
+            Ok(true)
+        }
+        Err(e) if e.is_retryable() => {
+            warn!("Failed to reply to DM {}: {}, retrying next check", event.id, e);
+            Ok(false)
+        }
+        Err(e) => {
+            error!("Failed to reply to DM {}: {}, giving up on it", event.id, e);
+            Ok(true)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{test_config, QuotaPriority};
+    use crate::state::{test_identity, SqliteStateStore};
+    use crate::templates::TweetTemplates;
+    use crate::twitter::{FakeClient, MockServer};
+
+    fn setup() -> (StateHandle, QuotaPlanner) {
+        let state = StateHandle::open(Box::new(SqliteStateStore::in_memory().unwrap())).unwrap();
+        (state, QuotaPlanner::new(500, QuotaPriority::Posts, 4))
+    }
+
+    fn brain() -> AgentBrain {
+        AgentBrain {
+            beliefs: build_knowledge_base(),
+            engine: build_reasoning_engine(),
+        }
+    }
+
+    #[test]
+    fn test_command_parsing() {
+        let parse = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            Command::parse(&args)
+        };
+        assert_eq!(parse(&[]).unwrap(), None);
+        assert_eq!(parse(&["delete", "42"]).unwrap(), Some(Command::Delete("42".to_string())));
+        assert_eq!(
+            parse(&["quote", "42", "Agents", "all", "the", "way"]).unwrap(),
+            Some(Command::Quote("42".to_string(), "Agents all the way".to_string()))
+        );
+        assert!(parse(&["quote", "42"]).is_err());
+        assert!(parse(&["boost", "42"]).is_err());
+        assert!(parse(&["like"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_post_tweet_records_post() {
+        let config = test_config();
+        let (state, quota) = setup();
+        let tracker = Mutex::new(state.read(|s| PostTracker::from_state(4, s)));
+        let client = FakeClient::new();
+
+        post_tweet(&client, &config, &tracker, &state, &quota).await.unwrap();
+
+        let posts = client.posts();
+        assert!(!posts.is_empty());
+        assert!(posts[0].in_reply_to.is_none());
+        let logged = state.read(|s| s.posts.clone());
+        assert_eq!(logged.len(), posts.len());
+        assert_eq!(logged[0].tweet_id, posts[0].id);
+        assert_eq!(logged[0].kind, PostKind::Original);
+        let tracked = state.read(|s| s.post_metrics.clone());
+        assert_eq!(tracked.len(), 1);
+        assert_eq!(tracked[0].tweet_id, posts[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_posts_use_different_templates() {
+        let config = BotConfig {
+            enable_crypto: false,
+            enable_meme: false,
+            enable_agentropic: false,
+            ..test_config()
+        };
+        let (state, quota) = setup();
+        let tracker = Mutex::new(state.read(|s| PostTracker::from_state(4, s)));
+        let client = FakeClient::new();
+
+        // No metrics come in between, yet the first post already counts
+        post_tweet(&client, &config, &tracker, &state, &quota).await.unwrap();
+        post_tweet(&client, &config, &tracker, &state, &quota).await.unwrap();
+
+        let tracked = state.read(|s| s.post_metrics.clone());
+        assert_eq!(tracked.len(), 2);
+        assert_ne!(tracked[0].origin, tracked[1].origin);
+    }
+
+    #[tokio::test]
+    async fn test_post_tweet_retries_after_server_error() {
+        let config = test_config();
+        let (state, quota) = setup();
+        let tracker = Mutex::new(state.read(|s| PostTracker::from_state(4, s)));
+        let client = FakeClient::new();
+        client.fail_writes(1);
+
+        post_tweet(&client, &config, &tracker, &state, &quota).await.unwrap();
+
+        let posts = client.posts();
+        assert!(!posts.is_empty());
+        assert_eq!(state.read(|s| s.posts.len()), posts.len());
+    }
+
+    #[tokio::test]
+    async fn test_post_poll_records_pending_poll() {
+        let (state, _) = setup();
+        let client = FakeClient::new();
+        let poll = TweetTemplates::poll_templates()[0];
+
+        post_poll(&client, &state, &poll, PostOrigin::new("poll", poll.question)).await.unwrap();
+
+        let posts = client.posts();
+        assert_eq!(posts[0].poll_options.len(), poll.options.len());
+        let pending = state.read(|s| s.pending_polls.clone());
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].tweet_id, posts[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_closed_polls_get_a_results_reply() {
+        let (state, quota) = setup();
+        let client = FakeClient::new();
+        let closed = Utc::now() - chrono::Duration::minutes(5);
+        state
+            .update(|s| {
+                for id in ["50", "51"] {
+                    s.pending_polls.push(PendingPoll {
+                        tweet_id: id.to_string(),
+                        question: "Which pattern do you use most?\n\n#Agentropic".to_string(),
+                        closes_at: closed,
+                    });
                 }
+            })
+            .unwrap();
+        client.push_poll(
+            r#"{"id":"70","voting_status":"closed","end_datetime":"2025-04-01T12:00:00.000Z",
+                "options":[{"position":1,"label":"Swarm","votes":1},{"position":2,"label":"Team","votes":3}]}"#,
+        );
 
-                // Rate limit: wait between replies
-                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-            }
-            None => {
-                warn!("Could not generate response for mention {}", mention.id);
-            }
+        summarize_closed_polls(&client, &state, &quota).await.unwrap();
+
+        // "51" came back without a poll and is dropped
+        let posts = client.posts();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].in_reply_to.as_deref(), Some("50"));
+        assert!(posts[0].text.contains("Team: 75%"));
+        assert!(state.read(|s| s.pending_polls.is_empty()));
+        assert_eq!(state.read(|s| s.reads_in_month(&Utc::now())), 2);
+    }
+
+    #[tokio::test]
+    async fn test_collects_metrics_for_recent_posts() {
+        let (state, _) = setup();
+        let now = Utc::now();
+        state
+            .update(|s| {
+                s.track_post("9", PostOrigin::new("meme", "lol"), now - chrono::Duration::days(10));
+                s.track_post("10", PostOrigin::new("ai", "Agent swarms"), now - chrono::Duration::hours(6));
+                s.track_post("11", PostOrigin::new("poll", "Which pattern?"), now - chrono::Duration::hours(2));
+            })
+            .unwrap();
+        let client = FakeClient::new();
+        client.push_metrics(
+            r#"{"data":[{"id":"10","text":"hi","public_metrics":{"retweet_count":2,"reply_count":1,
+                         "like_count":9,"quote_count":0,"impression_count":410}}],
+                "errors":[{"value":"11","title":"Not Found Error"}]}"#,
+        );
+
+        let updated = collect_post_metrics(&client, &state, 10).await.unwrap();
+
+        assert_eq!(updated, 1);
+        let metrics = state.read(|s| s.post_metrics.clone());
+        // The post past the window keeps its row but isn't looked up again
+        assert_eq!(metrics.len(), 3);
+        assert!(metrics[0].collected_at.is_none());
+        assert_eq!(metrics[1].counts.engagements(), 12);
+        assert_eq!(metrics[1].counts.impressions, Some(410));
+        assert_eq!(metrics[1].origin, PostOrigin::new("ai", "Agent swarms"));
+        assert!(metrics[2].collected_at.is_none());
+        assert_eq!(state.read(|s| s.reads_in_month(&now)), 1);
+
+        // With the read budget spent, nothing is looked up
+        assert_eq!(collect_post_metrics(&client, &state, 1).await.unwrap(), 0);
+        assert_eq!(state.read(|s| s.reads_in_month(&now)), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replies_to_mentions() {
+        let (state, quota) = setup();
+        let brain = brain();
+        let tracker = Mutex::new(MentionTracker::default());
+        let client = FakeClient::new();
+        client.push_mentions(
+            r#"{"data":[{"id":"31","text":"@agentropic what patterns do you support?","author_id":"7"},
+                        {"id":"30","text":"@agentropic talking to myself","author_id":"1000"}],
+                "meta":{"newest_id":"31","oldest_id":"30","result_count":2}}"#,
+        );
+
+        let found = check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, "1000", 50)
+            .await
+            .unwrap();
+
+        assert_eq!(found, 2);
+        let posts = client.posts();
+        assert!(!posts.is_empty());
+        assert!(posts.iter().all(|p| p.in_reply_to.is_some()));
+        assert_eq!(posts[0].in_reply_to.as_deref(), Some("31"));
+        assert!(posts[0].text.to_lowercase().contains("pattern"));
+        assert_eq!(state.read(|s| s.since_id.clone()).as_deref(), Some("31"));
+        assert_eq!(state.read(|s| s.reads_in_month(&Utc::now())), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_mentions_stay_ahead_of_the_cursor() {
+        let (state, quota) = setup();
+        let brain = brain();
+        let tracker = Mutex::new(MentionTracker::default());
+        let client = FakeClient::new();
+        let page = r#"{"data":[{"id":"32","text":"@agentropic what patterns do you support?","author_id":"8"},
+                               {"id":"31","text":"@agentropic what patterns do you support?","author_id":"7"}],
+                       "meta":{"newest_id":"32","oldest_id":"31","result_count":2}}"#;
+        let since_id = || state.read(|s| s.since_id.clone());
+
+        // The first reply fails with a server error, so neither mention is passed
+        client.push_mentions(page);
+        client.fail_writes(1);
+        check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, "1000", 50)
+            .await
+            .unwrap();
+        assert!(client.posts().is_empty());
+        assert_eq!(since_id(), None);
+
+        // One write left in the budget: 31 is answered, 32 waits
+        let tight = QuotaPlanner::new(1, QuotaPriority::Replies, 4);
+        client.push_mentions(page);
+        check_and_reply_mentions(&client, &brain, &tracker, &state, &tight, "1000", 50)
+            .await
+            .unwrap();
+        let replied_to: Vec<String> = client.posts().into_iter().filter_map(|p| p.in_reply_to).collect();
+        assert_eq!(replied_to, vec!["31".to_string()]);
+        assert_eq!(since_id().as_deref(), Some("31"));
+
+        client.push_mentions(page);
+        check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, "1000", 50)
+            .await
+            .unwrap();
+        let replied_to: Vec<String> = client.posts().into_iter().filter_map(|p| p.in_reply_to).collect();
+        assert_eq!(replied_to, vec!["31".to_string(), "32".to_string()]);
+        assert_eq!(since_id().as_deref(), Some("32"));
+    }
+
+    #[tokio::test]
+    async fn test_capped_fetch_resumes_older_mentions_next_check() {
+        let (state, quota) = setup();
+        state.update(|s| s.since_id = Some("10".to_string())).unwrap();
+        let brain = brain();
+        let tracker = Mutex::new(MentionTracker {
+            last_seen_id: Some("10".to_string()),
+            ..Default::default()
+        });
+        let client = FakeClient::new();
+        client.push_mentions(
+            r#"{"data":[{"id":"41","text":"@agentropic what patterns do you support?","author_id":"7"}],
+                "meta":{"newest_id":"41","oldest_id":"41","result_count":1,"next_token":"older"}}"#,
+        );
+        client.push_mentions(
+            r#"{"data":[{"id":"40","text":"@agentropic how does messaging work?","author_id":"8"}],
+                "meta":{"newest_id":"40","oldest_id":"40","result_count":1}}"#,
+        );
+
+        let found = check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, "1000", 1)
+            .await
+            .unwrap();
+        assert_eq!(found, 1);
+        // The cursor waits behind the mention that wasn't fetched yet
+        assert_eq!(state.read(|s| s.since_id.clone()).as_deref(), Some("10"));
+        assert!(state.read(|s| s.mention_backlog.is_some()));
+
+        let found = check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, "1000", 1)
+            .await
+            .unwrap();
+        assert_eq!(found, 1);
+        let replied_to: Vec<Option<String>> = client.posts().into_iter().map(|p| p.in_reply_to).collect();
+        assert!(replied_to.contains(&Some("41".to_string())));
+        assert!(replied_to.contains(&Some("40".to_string())));
+        assert_eq!(state.read(|s| s.since_id.clone()).as_deref(), Some("41"));
+        assert!(state.read(|s| s.mention_backlog.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_verify_identity_caches_account_and_rejects_other_handles() {
+        let (state, _) = setup();
+        let client = FakeClient::new();
+
+        let identity = verify_identity(&client, &test_config(), &state).await.unwrap();
+        assert_eq!(identity.user_id, "1000");
+        assert_eq!(state.read(|s| s.identity.clone()), Some(identity));
+
+        let config = BotConfig {
+            bot_username: "someone_else".to_string(),
+            ..test_config()
+        };
+        let err = verify_identity(&client, &config, &state).await.unwrap_err();
+        assert!(err.to_string().contains("@test"));
+
+        let config = BotConfig {
+            twitter_user_id: Some("2000".to_string()),
+            ..test_config()
+        };
+        let err = verify_identity(&client, &config, &state).await.unwrap_err();
+        assert!(err.to_string().contains("TWITTER_USER_ID 2000"));
+    }
+
+    #[tokio::test]
+    async fn test_pushed_mentions_are_answered_once_in_any_order() {
+        let (state, quota) = setup();
+        let brain = brain();
+        let tracker = Mutex::new(MentionTracker::default());
+        let client = FakeClient::new();
+        let batch = |json: &str| -> MentionsResponse { serde_json::from_str(json).unwrap() };
+
+        let first = batch(
+            r#"{"data":[{"id":"32","text":"@agentropic what patterns do you support?","author_id":"7"},
+                        {"id":"31","text":"@agentropic what patterns do you support?","author_id":"8"}],
+                "meta":{"newest_id":"32","oldest_id":"31","result_count":2}}"#,
+        );
+        // Delivered late, behind the cursor
+        let late = batch(
+            r#"{"data":[{"id":"30","text":"@agentropic what patterns do you support?","author_id":"9"}],
+                "meta":{"newest_id":"30","oldest_id":"30","result_count":1}}"#,
+        );
+        for mentions in [&first, &late, &first] {
+            reply_to_mentions(&client, &brain, &tracker, &state, &quota, "1000", mentions)
+                .await
+                .unwrap();
         }
+
+        let replied_to: Vec<String> = client.posts().into_iter().filter_map(|p| p.in_reply_to).collect();
+        for id in ["30", "31", "32"] {
+            assert_eq!(replied_to.iter().filter(|r| *r == id).count(), 1, "mention {}", id);
+        }
+        assert_eq!(tracker.lock().await.last_seen_id.as_deref(), Some("32"));
     }
 
-    Ok(())
+    #[tokio::test]
+    async fn test_search_engagement_replies_once_and_likes_the_rest() {
+        let (state, quota) = setup();
+        let brain = brain();
+        let identity = test_identity();
+        let config = BotConfig {
+            search_queries: vec!["agentropic -from:test".to_string()],
+            ..test_config()
+        };
+        let client = FakeClient::new();
+        client.push_search(
+            r#"{"data":[{"id":"52","text":"How does swarm consensus work in agentropic?","author_id":"7"},
+                        {"id":"51","text":"is the auction demo any good?","author_id":"8"},
+                        {"id":"50","text":"lunch was good","author_id":"9"}],
+                "meta":{"newest_id":"52","oldest_id":"50","result_count":3}}"#,
+        );
+
+        let engaged = engage_with_search(&client, &brain, &state, &quota, &identity, &config)
+            .await
+            .unwrap();
+
+        assert_eq!(engaged, 2);
+        let posts = client.posts();
+        assert!(!posts.is_empty());
+        assert!(posts.iter().all(|p| p.quote_of.is_none() && p.in_reply_to.is_some()));
+        assert_eq!(client.likes().len(), 1);
+        assert!(state.read(|s| s.has_engaged("51") && s.has_engaged("52") && !s.has_engaged("50")));
+        assert_eq!(
+            state.read(|s| s.search_since_ids.get(&config.search_queries[0]).cloned()).as_deref(),
+            Some("52")
+        );
+
+        // Today's budget is spent, so nothing is searched
+        let config = BotConfig {
+            search_daily_budget: 2,
+            ..config
+        };
+        let engaged = engage_with_search(&client, &brain, &state, &quota, &identity, &config)
+            .await
+            .unwrap();
+        assert_eq!(engaged, 0);
+        assert_eq!(state.read(|s| s.reads_in_month(&Utc::now())), 1);
+
+        // So is this month's read budget
+        let config = BotConfig {
+            search_daily_budget: 10,
+            monthly_read_budget: 1,
+            ..config
+        };
+        let engaged = engage_with_search(&client, &brain, &state, &quota, &identity, &config)
+            .await
+            .unwrap();
+        assert_eq!(engaged, 0);
+        assert_eq!(state.read(|s| s.reads_in_month(&Utc::now())), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replies_to_new_dms_once() {
+        let (state, _) = setup();
+        state.update(|s| s.dm_since_id = Some("898".to_string())).unwrap();
+        let brain = brain();
+        let client = FakeClient::new();
+        let page = r#"{"data":[{"id":"901","event_type":"MessageCreate","text":"thanks!","sender_id":"1000"},
+                        {"id":"900","event_type":"MessageCreate","text":"how does messaging work?","sender_id":"7"},
+                        {"id":"899","event_type":"MessageCreate","text":"what patterns are there?","sender_id":"8"},
+                        {"id":"898","event_type":"MessageCreate","text":"already answered","sender_id":"9"}],
+                "includes":{"users":[{"id":"7","username":"alice"}]},
+                "meta":{"result_count":4}}"#;
+        let since_id = || state.read(|s| s.dm_since_id.clone());
+
+        // A failed send leaves the DM for the next check
+        client.push_dms(page);
+        client.fail_writes(1);
+        assert_eq!(check_and_reply_dms(&client, &brain, &state, "1000", 1, 100).await.unwrap(), 3);
+        assert!(client.dms().is_empty());
+        assert_eq!(since_id().as_deref(), Some("898"));
+
+        // Oldest first, and the daily cap of one stops the second reply
+        client.push_dms(page);
+        assert_eq!(check_and_reply_dms(&client, &brain, &state, "1000", 1, 100).await.unwrap(), 3);
+        let dms = client.dms();
+        assert_eq!(dms.len(), 1);
+        assert_eq!(dms[0].0, "8");
+        assert!(dms[0].1.to_lowercase().contains("pattern"));
+        assert_eq!(since_id().as_deref(), Some("899"));
+
+        // The DM held back by the cap is answered once there's room
+        client.push_dms(page);
+        assert_eq!(check_and_reply_dms(&client, &brain, &state, "1000", 2, 100).await.unwrap(), 2);
+        let dms = client.dms();
+        assert_eq!(dms.len(), 2);
+        assert_eq!(dms[1].0, "7");
+        assert!(client.posts().is_empty());
+        assert_eq!(since_id().as_deref(), Some("901"));
+        assert_eq!(state.read(|s| s.dm_replies_on(&Utc::now())), 2);
+
+        // Nothing is fetched once the month's reads are spent
+        client.push_dms(page);
+        assert_eq!(check_and_reply_dms(&client, &brain, &state, "1000", 5, 3).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_new_followers_are_welcomed_and_followed_back_within_cap() {
+        let (state, _) = setup();
+        let brain = brain();
+        let config = BotConfig {
+            welcome_dms: true,
+            follow_back: true,
+            follow_back_keywords: vec!["rust".to_string()],
+            max_follower_actions_per_day: 3,
+            ..test_config()
+        };
+        let policy = FollowerPolicy::from_config(&config);
+        let client = FakeClient::new();
+        client.push_followers(r#"{"data":[{"id":"1","username":"old"}]}"#);
+        client.push_followers(
+            r#"{"data":[{"id":"4","username":"carol","description":"rust","created_at":"2019-01-01T00:00:00Z"},
+                        {"id":"3","username":"bob","description":"crypto","created_at":"2019-01-01T00:00:00Z"},
+                        {"id":"2","username":"alice","description":"Rust agents","created_at":"2019-01-01T00:00:00Z"},
+                        {"id":"1","username":"old"}]}"#,
+        );
+
+        // The first check only records who already follows
+        assert_eq!(handle_new_followers(&client, &brain, &state, "1000", &policy, 100).await.unwrap(), 0);
+        assert!(client.dms().is_empty());
+
+        let found = handle_new_followers(&client, &brain, &state, "1000", &policy, 100).await.unwrap();
+
+        assert_eq!(found, 3);
+        // alice: welcome + follow, bob: welcome, carol: over the cap
+        let welcomed: Vec<String> = client.dms().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(welcomed, vec!["2", "3"]);
+        assert!(client.dms()[0].1.contains("@alice"));
+        assert_eq!(client.follows(), vec!["2"]);
+        let snapshot = state.read(|s| s.follower_snapshot.clone()).unwrap();
+        assert!(snapshot.contains("3") && !snapshot.contains("4"));
+        assert_eq!(state.read(|s| s.follower_actions_on(&Utc::now())), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failed_welcome_is_retried_next_check() {
+        let (state, _) = setup();
+        let brain = brain();
+        let policy = FollowerPolicy::from_config(&BotConfig {
+            welcome_dms: true,
+            ..test_config()
+        });
+        let client = FakeClient::new();
+        let followers = r#"{"data":[{"id":"3","username":"bob"},{"id":"2","username":"alice"},{"id":"1","username":"old"}]}"#;
+        client.push_followers(r#"{"data":[{"id":"1","username":"old"}]}"#);
+        client.push_followers(followers);
+        client.push_followers(followers);
+
+        handle_new_followers(&client, &brain, &state, "1000", &policy, 100).await.unwrap();
+        client.fail_writes(1);
+        assert_eq!(handle_new_followers(&client, &brain, &state, "1000", &policy, 100).await.unwrap(), 2);
+        let snapshot = state.read(|s| s.follower_snapshot.clone()).unwrap();
+        assert!(snapshot.contains("3") && !snapshot.contains("2"));
+
+        assert_eq!(handle_new_followers(&client, &brain, &state, "1000", &policy, 100).await.unwrap(), 1);
+        let welcomed: Vec<String> = client.dms().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(welcomed, vec!["3", "2"]);
+
+        // No reads are left for a fourth check
+        assert_eq!(handle_new_followers(&client, &brain, &state, "1000", &policy, 3).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_post_tweet_against_mock_api() {
+        let config = test_config();
+        let (state, quota) = setup();
+        let tracker = Mutex::new(state.read(|s| PostTracker::from_state(4, s)));
+        let server = MockServer::start().await;
+
+        post_tweet(&server.client(), &config, &tracker, &state, &quota)
+            .await
+            .unwrap();
+
+        let posted = server.posted();
+        assert!(!posted.is_empty());
+        let logged = state.read(|s| s.posts.clone());
+        assert_eq!(logged[0].tweet_id, posted[0]["id"].as_str().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_replies_to_mentions_against_mock_api() {
+        let (state, quota) = setup();
+        let brain = brain();
+        let tracker = Mutex::new(MentionTracker::default());
+        let server = MockServer::start().await;
+        server.set_authenticated_user("test", "1000");
+        server.add_mention(41, "7", "@agentropic what patterns do you support?");
+        let client = server.client();
+
+        let identity = verify_identity(&client, &test_config(), &state).await.unwrap();
+        let found = check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, &identity.user_id, 50)
+            .await
+            .unwrap();
+
+        assert_eq!(found, 1);
+        let posted = server.posted();
+        assert!(!posted.is_empty());
+        assert_eq!(posted[0]["reply"]["in_reply_to_tweet_id"], "41");
+        assert_eq!(state.read(|s| s.since_id.clone()).as_deref(), Some("41"));
+    }
+
+    #[tokio::test]
+    async fn test_replies_to_recorded_mentions() {
+        let (state, quota) = setup();
+        let brain = brain();
+        let tracker = Mutex::new(MentionTracker::default());
+        let cassette = Cassette::replay(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/cassettes/mentions_suspended_author.json"
+        ))
+        .unwrap();
+        let client = TwitterClient::with_credentials(
+            "key".to_string(),
+            "secret".to_string(),
+            "token".to_string(),
+            "token-secret".to_string(),
+        )
+        .with_cassette(cassette);
+
+        let found = check_and_reply_mentions(&client, &brain, &tracker, &state, &quota, "1000", 50)
+            .await
+            .unwrap();
+
+        assert_eq!(found, 3);
+        assert_eq!(state.read(|s| s.since_id.clone()).as_deref(), Some("52"));
+        let replies = state.read(|s| s.posts.clone());
+        assert!(!replies.is_empty());
+        assert!(replies.iter().all(|p| p.kind == PostKind::Reply));
+    }
 }
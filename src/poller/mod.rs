@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use crate::state::{next_month_start, BotState};
+
+/// Mention poll scheduler that adapts to activity and the monthly read budget.
+///
+/// Each empty poll doubles the interval up to `max_interval`; any mention
+/// snaps it back to `min_interval`. The interval never drops below what the
+/// remaining reads can sustain until the month rolls over.
+pub struct AdaptivePoller {
+    min_interval: Duration,
+    max_interval: Duration,
+    monthly_read_budget: u32,
+    current: Duration,
+}
+
+impl AdaptivePoller {
+    pub fn new(min_interval: Duration, max_interval: Duration, monthly_read_budget: u32) -> Self {
+        Self {
+            min_interval,
+            max_interval,
+            monthly_read_budget,
+            current: min_interval,
+        }
+    }
+
+    /// Record how many mentions the last poll found and return the delay before the next one
+    pub fn next_delay(&mut self, found: usize, state: &BotState, now: DateTime<Utc>) -> Duration {
+        self.current = if found > 0 {
+            self.min_interval
+        } else {
+            (self.current * 2).min(self.max_interval)
+        };
+        self.current.max(self.budget_interval(state, now))
+    }
+
+    /// Reads this month if polling continues every `interval` until month end
+    pub fn projected_reads(&self, state: &BotState, now: DateTime<Utc>, interval: Duration) -> u32 {
+        let secs_left = (next_month_start(now) - now).num_seconds().max(0) as u64;
+        let upcoming = secs_left / interval.as_secs().max(1);
+        state.reads_in_month(&now) + upcoming as u32
+    }
+
+    /// Shortest interval that spreads the remaining reads over the rest of the month
+    fn budget_interval(&self, state: &BotState, now: DateTime<Utc>) -> Duration {
+        let secs_left = (next_month_start(now) - now).num_seconds().max(1) as u64;
+        let remaining = self
+            .monthly_read_budget
+            .saturating_sub(state.reads_in_month(&now));
+        if remaining == 0 {
+            // Out of reads: sit out the rest of the month
+            return Duration::from_secs(secs_left);
+        }
+        Duration::from_secs(secs_left / remaining as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn poller() -> AdaptivePoller {
+        AdaptivePoller::new(Duration::from_secs(60), Duration::from_secs(3600), 100_000)
+    }
+
+    #[test]
+    fn test_backs_off_while_quiet() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap();
+        let state = BotState::default();
+        let mut p = poller();
+        assert_eq!(p.next_delay(0, &state, now), Duration::from_secs(120));
+        assert_eq!(p.next_delay(0, &state, now), Duration::from_secs(240));
+        for _ in 0..10 {
+            p.next_delay(0, &state, now);
+        }
+        assert_eq!(p.next_delay(0, &state, now), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_activity_resets_interval() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap();
+        let state = BotState::default();
+        let mut p = poller();
+        p.next_delay(0, &state, now);
+        p.next_delay(0, &state, now);
+        assert_eq!(p.next_delay(3, &state, now), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_budget_stretches_interval() {
+        // 30 days left, 30 reads left: at most one poll per day
+        let now = Utc.with_ymd_and_hms(2025, 4, 1, 0, 0, 0).unwrap();
+        let mut state = BotState::default();
+        for _ in 0..70 {
+            state.record_read(&now);
+        }
+        let mut p = AdaptivePoller::new(Duration::from_secs(60), Duration::from_secs(3600), 100);
+        let delay = p.next_delay(5, &state, now);
+        assert_eq!(delay, Duration::from_secs(86_400));
+        assert!(p.projected_reads(&state, now, delay) <= 100);
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+use tracing::debug;
+
+use crate::config::QuotaPriority;
+use crate::state::{month_start, next_month_start, BotState, PostKind};
+
+/// Projected write usage for the current month
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotaForecast {
+    pub budget: u32,
+    pub used_originals: u32,
+    pub used_replies: u32,
+    pub projected: u32,
+    pub days_left: f64,
+}
+
+impl QuotaForecast {
+    pub fn used(&self) -> u32 {
+        self.used_originals + self.used_replies
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.budget.saturating_sub(self.used())
+    }
+}
+
+/// Spreads the monthly write budget (posts + replies) across the month.
+///
+/// The priority kind may always write while budget remains. The other kind
+/// only gets what is left after reserving the priority kind's projected
+/// usage, paced evenly over the remaining days.
+pub struct QuotaPlanner {
+    monthly_budget: u32,
+    priority: QuotaPriority,
+    posts_per_day: u32,
+}
+
+impl QuotaPlanner {
+    pub fn new(monthly_budget: u32, priority: QuotaPriority, posts_per_day: u32) -> Self {
+        Self {
+            monthly_budget,
+            priority,
+            posts_per_day,
+        }
+    }
+
+    pub fn forecast(&self, state: &BotState, now: DateTime<Utc>) -> QuotaForecast {
+        let used_originals = state.posts_in_month(&now, PostKind::Original);
+        let used_replies = state.posts_in_month(&now, PostKind::Reply);
+        let days_left = days_left_in_month(now);
+
+        let projected_originals = used_originals as f64 + self.posts_per_day as f64 * days_left;
+        let projected_replies =
+            used_replies as f64 + daily_rate(used_replies, now) * days_left;
+
+        QuotaForecast {
+            budget: self.monthly_budget,
+            used_originals,
+            used_replies,
+            projected: (projected_originals + projected_replies).ceil() as u32,
+            days_left,
+        }
+    }
+
+    /// Whether one more write of `kind` fits the monthly plan
+    pub fn allows(&self, kind: PostKind, state: &BotState, now: DateTime<Utc>) -> bool {
+        let forecast = self.forecast(state, now);
+        let remaining = forecast.remaining();
+        if remaining == 0 {
+            return false;
+        }
+        let priority_kind = match self.priority {
+            QuotaPriority::Posts => PostKind::Original,
+            QuotaPriority::Replies => PostKind::Reply,
+        };
+        if kind == priority_kind || forecast.projected <= self.monthly_budget {
+            return true;
+        }
+
+        // Over forecast: hold back what the priority kind is expected to need
+        let reserve = match self.priority {
+            QuotaPriority::Posts => self.posts_per_day as f64 * forecast.days_left,
+            QuotaPriority::Replies => daily_rate(forecast.used_replies, now) * forecast.days_left,
+        };
+        let available = (remaining as f64 - reserve.ceil()).max(0.0);
+
+        // Pace what's left evenly, counting today's writes of this kind as already spent
+        let today = state.posts_on(&now, kind) as f64;
+        let days = forecast.days_left.ceil().max(1.0);
+        let allowance_today = (available + today) / days;
+        debug!(
+            "Quota over forecast ({} > {}): {:?} allowance today {:.2}, used {}",
+            forecast.projected, self.monthly_budget, kind, allowance_today, today
+        );
+        available > 0.0 && today < allowance_today
+    }
+}
+
+fn days_left_in_month(now: DateTime<Utc>) -> f64 {
+    (next_month_start(now) - now).num_seconds() as f64 / 86_400.0
+}
+
+/// Average per-day count so far this month; the first day counts as a full day
+fn daily_rate(count: u32, now: DateTime<Utc>) -> f64 {
+    let elapsed = (now - month_start(now)).num_seconds() as f64 / 86_400.0;
+    count as f64 / elapsed.max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::PostRecord;
+    use chrono::TimeZone;
+
+    fn state_with(originals: u32, replies: u32, at: DateTime<Utc>) -> BotState {
+        let mut state = BotState::default();
+        let mut add = |kind, n| {
+            for i in 0..n {
+                state.record_post(PostRecord {
+                    tweet_id: format!("{:?}{}", kind, i),
+                    kind,
+                    text: String::new(),
+                    in_reply_to: None,
+                    posted_at: at,
+                });
+            }
+        };
+        add(PostKind::Original, originals);
+        add(PostKind::Reply, replies);
+        state
+    }
+
+    #[test]
+    fn test_under_forecast_allows_everything() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 15, 12, 0, 0).unwrap();
+        let planner = QuotaPlanner::new(500, QuotaPriority::Posts, 4);
+        let state = state_with(56, 20, now);
+        assert!(planner.forecast(&state, now).projected <= 500);
+        assert!(planner.allows(PostKind::Original, &state, now));
+        assert!(planner.allows(PostKind::Reply, &state, now));
+    }
+
+    #[test]
+    fn test_exhausted_budget_blocks_all() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 20, 12, 0, 0).unwrap();
+        let planner = QuotaPlanner::new(100, QuotaPriority::Posts, 4);
+        let state = state_with(60, 40, now);
+        assert!(!planner.allows(PostKind::Original, &state, now));
+        assert!(!planner.allows(PostKind::Reply, &state, now));
+    }
+
+    #[test]
+    fn test_posts_priority_throttles_replies() {
+        // 10 days left needs ~40 posts; only 30 remain, so replies get nothing
+        let now = Utc.with_ymd_and_hms(2025, 4, 21, 0, 0, 0).unwrap();
+        let planner = QuotaPlanner::new(200, QuotaPriority::Posts, 4);
+        let state = state_with(80, 90, now);
+        assert!(planner.allows(PostKind::Original, &state, now));
+        assert!(!planner.allows(PostKind::Reply, &state, now));
+    }
+
+    #[test]
+    fn test_replies_priority_throttles_posts() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 21, 0, 0, 0).unwrap();
+        let planner = QuotaPlanner::new(200, QuotaPriority::Replies, 4);
+        let state = state_with(20, 150, now);
+        assert!(planner.allows(PostKind::Reply, &state, now));
+        assert!(!planner.allows(PostKind::Original, &state, now));
+    }
+
+    #[test]
+    fn test_days_left_wraps_year() {
+        let now = Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap();
+        assert!((days_left_in_month(now) - 1.0).abs() < 1e-9);
+    }
+}
@@ -1,6 +1,8 @@
 use agentropic_cognition::{Belief, BeliefBase, ReasoningEngine, Rule, UtilityFunction};
 use tracing::{info, debug};
 
+use crate::thread::split_thread;
+
 /// Build the reasoning engine with topic-matching rules
 pub fn build_reasoning_engine() -> ReasoningEngine {
     let mut engine = ReasoningEngine::new();
@@ -219,13 +221,14 @@ fn lookup(beliefs: &BeliefBase, key: &str) -> String {
         .unwrap_or_else(|| format!("(no info on '{}')", key))
 }
 
-/// Score response candidates and pick the best one that fits in 280 chars
+/// Score response candidates, preferring ones that fit in 280 chars.
+/// Longer candidates only win when nothing fits; they go out as a thread.
 fn select_best_response(candidates: Vec<String>) -> Option<String> {
     let fit_scorer = UtilityFunction::new("tweet_fit", |state: &[String]| {
         if let Some(text) = state.first() {
             let len = text.len();
             if len > 280 {
-                return 0.0;
+                return 0.1;
             }
             if len > 250 {
                 return 0.3;
@@ -268,31 +271,37 @@ fn select_best_response(candidates: Vec<String>) -> Option<String> {
     best.map(|(_, text)| text)
 }
 
-/// Truncate response to fit tweet limit
-fn truncate_to_tweet(text: String) -> String {
-    if text.len() <= 280 {
-        return text;
-    }
-    let truncated = &text[..277];
-    if let Some(last_space) = truncated.rfind(' ') {
-        format!("{}...", &text[..last_space])
-    } else {
-        format!("{}...", &text[..277])
-    }
+/// Welcome DM for a new follower, pointing at how to get started
+pub fn welcome_message(username: &str, beliefs: &BeliefBase) -> String {
+    format!(
+        "Thanks for the follow, @{}! {} {}",
+        username,
+        lookup(beliefs, "install"),
+        lookup(beliefs, "examples")
+    )
 }
 
-/// Main entry point: given a mention text, generate a response using ReasoningEngine
+/// Topic the engine infers for `text` and its confidence, if any rule matches
+pub fn match_topic(text: &str, engine: &ReasoningEngine) -> Option<(String, f64)> {
+    let facts = extract_facts(text);
+    let inference = engine.best_match(&facts)?;
+    let topic = inference.conclusions.first()?.clone();
+    Some((topic, inference.confidence))
+}
+
+/// Main entry point: given a mention text, generate a response using ReasoningEngine.
+/// Returns the reply as thread parts; usually just one.
 pub fn generate_response(
     mention_text: &str,
     beliefs: &BeliefBase,
     engine: &ReasoningEngine,
-) -> Option<String> {
+) -> Option<Vec<String>> {
     let facts = extract_facts(mention_text);
 
     if facts.is_empty() {
         info!("No facts extracted from mention, using default response");
         let candidates = get_response_candidates("unknown", beliefs);
-        return select_best_response(candidates).map(truncate_to_tweet);
+        return select_best_response(candidates).map(|text| split_thread(&text));
     }
 
     info!("Extracted facts: {:?}", facts);
@@ -319,7 +328,7 @@ pub fn generate_response(
     };
 
     let candidates = get_response_candidates(&topic, beliefs);
-    select_best_response(candidates).map(truncate_to_tweet)
+    select_best_response(candidates).map(|text| split_thread(&text))
 }
 
 #[cfg(test)]
@@ -344,8 +353,9 @@ mod tests {
         let (beliefs, engine) = setup();
         let response = generate_response("@agentropic what patterns do you support?", &beliefs, &engine);
         assert!(response.is_some());
-        let text = response.unwrap();
-        assert!(text.len() <= 280);
+        let parts = response.unwrap();
+        assert!(parts.iter().all(|p| p.len() <= 280));
+        let text = parts.join(" ");
         assert!(text.to_lowercase().contains("pattern"));
     }
 
@@ -354,8 +364,9 @@ mod tests {
         let (beliefs, engine) = setup();
         let response = generate_response("@agentropic explain BDI belief desire intention", &beliefs, &engine);
         assert!(response.is_some());
-        let text = response.unwrap();
-        assert!(text.len() <= 280);
+        let parts = response.unwrap();
+        assert!(parts.iter().all(|p| p.len() <= 280));
+        let text = parts.join(" ");
         assert!(text.to_lowercase().contains("belief") || text.to_lowercase().contains("bdi"));
     }
 
@@ -364,8 +375,27 @@ mod tests {
         let (beliefs, engine) = setup();
         let response = generate_response("@agentropic how does the swarm work?", &beliefs, &engine);
         assert!(response.is_some());
-        let text = response.unwrap();
-        assert!(text.len() <= 280);
+        let parts = response.unwrap();
+        assert!(parts.iter().all(|p| p.len() <= 280));
+    }
+
+    #[test]
+    fn test_match_topic() {
+        let (_, engine) = setup();
+        let (topic, confidence) = match_topic("Swarm consensus is hard to get right", &engine).unwrap();
+        assert_eq!(topic, "topic:swarm");
+        assert!(confidence > 0.0);
+        assert!(match_topic("xyzzy blorp", &engine).is_none());
+    }
+
+    #[test]
+    fn test_welcome_message_uses_knowledge_base() {
+        let (beliefs, _) = setup();
+        let message = welcome_message("alice", &beliefs);
+        assert!(message.starts_with("Thanks for the follow, @alice!"));
+        assert!(message.contains("agentropic-examples"));
+        assert!(!message.contains("(no info on"));
+        assert!(message.len() <= 280);
     }
 
     #[test]
@@ -373,7 +403,7 @@ mod tests {
         let (beliefs, engine) = setup();
         let response = generate_response("@agentropic xyzzy blorp", &beliefs, &engine);
         assert!(response.is_some());
-        assert!(response.unwrap().len() <= 280);
+        assert!(response.unwrap().iter().all(|p| p.len() <= 280));
     }
 
     #[test]
@@ -397,7 +427,11 @@ mod tests {
         for query in queries {
             let response = generate_response(query, &beliefs, &engine);
             assert!(response.is_some(), "No response for: {}", query);
-            assert!(response.unwrap().len() <= 280, "Too long for: {}", query);
+            assert!(
+                response.unwrap().iter().all(|p| p.len() <= 280),
+                "Too long for: {}",
+                query
+            );
         }
     }
 }
@@ -0,0 +1,149 @@
+use agentropic_cognition::ReasoningEngine;
+use std::collections::HashSet;
+
+use crate::responder::match_topic;
+use crate::state::{BotState, EngagementKind, Identity};
+use crate::twitter::MentionData;
+
+/// Hits per run that get a quote or reply; the rest are liked. Unprompted
+/// replies read as spam quickly, so they stay rare.
+const CONVERSATIONS_PER_RUN: usize = 1;
+
+/// A search result the reasoning engine recognised as one of our topics
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub tweet: MentionData,
+    pub topic: String,
+    pub confidence: f64,
+}
+
+/// Keep the results worth engaging with, best match first.
+///
+/// Drops duplicates across queries, the bot's own tweets, retweets, tweets
+/// already engaged with and anything no topic rule matches. Tweets that
+/// mention the bot are left to the mention job.
+pub fn rank_hits(
+    results: Vec<MentionData>,
+    engine: &ReasoningEngine,
+    identity: &Identity,
+    state: &BotState,
+) -> Vec<Hit> {
+    let handle = format!("@{}", identity.username.to_lowercase());
+    let mut seen = HashSet::new();
+    let mut hits: Vec<Hit> = results
+        .into_iter()
+        .filter(|t| seen.insert(t.id.clone()))
+        .filter(|t| t.author_id != identity.user_id && !t.is_retweet() && !state.has_engaged(&t.id))
+        .filter(|t| !t.text.to_lowercase().contains(&handle))
+        .filter_map(|tweet| {
+            let (topic, confidence) = match_topic(&tweet.text, engine)?;
+            Some(Hit {
+                tweet,
+                topic,
+                confidence,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    hits
+}
+
+/// Decide what to do with each hit, spending at most `budget` engagements.
+/// The strongest hits get a reply when they ask a question and a quote
+/// otherwise.
+pub fn plan(hits: Vec<Hit>, budget: u32) -> Vec<(Hit, EngagementKind)> {
+    hits.into_iter()
+        .take(budget as usize)
+        .enumerate()
+        .map(|(rank, hit)| {
+            let kind = if rank >= CONVERSATIONS_PER_RUN {
+                EngagementKind::Like
+            } else if hit.tweet.text.contains('?') {
+                EngagementKind::Reply
+            } else {
+                EngagementKind::Quote
+            };
+            (hit, kind)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responder::build_reasoning_engine;
+    use crate::state::{test_identity, EngagementRecord};
+    use chrono::Utc;
+
+    fn tweet(id: &str, author: &str, text: &str) -> MentionData {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "text": text,
+            "author_id": author,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rank_hits_filters_and_orders() {
+        let engine = build_reasoning_engine();
+        let mut state = BotState::default();
+        state.record_engagement(EngagementRecord {
+            tweet_id: "4".to_string(),
+            kind: EngagementKind::Like,
+            at: Utc::now(),
+        });
+        let mut retweet = tweet("5", "7", "RT swarm consensus");
+        retweet.referenced_tweets = serde_json::from_value(serde_json::json!([
+            {"type": "retweeted", "id": "1"}
+        ]))
+        .unwrap();
+
+        let results = vec![
+            tweet("1", "7", "swarm consensus with drone flocks and foraging"),
+            tweet("2", "1000", "our swarm consensus docs"),
+            tweet("3", "8", "xyzzy blorp"),
+            tweet("4", "9", "swarm consensus"),
+            retweet,
+            tweet("6", "10", "tried the auction demo"),
+            tweet("7", "11", "@Test how does swarm consensus work?"),
+            tweet("1", "7", "swarm consensus with drone flocks and foraging"),
+        ];
+        let identity = test_identity();
+        let hits = rank_hits(results, &engine, &identity, &state);
+
+        let ids: Vec<&str> = hits.iter().map(|h| h.tweet.id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"1") && ids.contains(&"6"));
+        assert!(hits[0].confidence >= hits[1].confidence);
+    }
+
+    #[test]
+    fn test_plan_converses_with_best_hit_only() {
+        let hit = |id: &str, text: &str| Hit {
+            tweet: tweet(id, "7", text),
+            topic: "topic:swarm".to_string(),
+            confidence: 0.5,
+        };
+        let hits = vec![
+            hit("1", "how does swarm consensus work?"),
+            hit("2", "swarm consensus is neat"),
+            hit("3", "more swarm"),
+        ];
+
+        let kinds: Vec<EngagementKind> =
+            plan(hits.clone(), 10).into_iter().map(|(_, k)| k).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                EngagementKind::Reply,
+                EngagementKind::Like,
+                EngagementKind::Like
+            ]
+        );
+
+        let planned = plan(hits[1..].to_vec(), 1);
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].1, EngagementKind::Quote);
+    }
+}
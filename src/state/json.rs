@@ -0,0 +1,68 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use super::{BotState, StateStore};
+
+/// Stores the whole state as a single pretty-printed JSON file
+pub struct JsonStateStore {
+    path: PathBuf,
+}
+
+impl JsonStateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl StateStore for JsonStateStore {
+    fn load(&self) -> Result<BotState> {
+        if !self.path.exists() {
+            return Ok(BotState::default());
+        }
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse {}", self.path.display()))
+    }
+
+    fn save(&self, state: &BotState) -> Result<()> {
+        // Write to a temp file and rename so a crash never leaves a half-written file
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(state)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to replace {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{PostKind, PostRecord};
+    use chrono::Utc;
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir().join(format!("xbot-state-{}.json", rand::random::<u64>()));
+        let store = JsonStateStore::new(&path);
+        assert_eq!(store.load().unwrap(), BotState::default());
+
+        let mut state = BotState {
+            since_id: Some("42".to_string()),
+            ..Default::default()
+        };
+        state.record_post(PostRecord {
+            tweet_id: "100".to_string(),
+            kind: PostKind::Original,
+            text: "hello".to_string(),
+            in_reply_to: None,
+            posted_at: Utc::now(),
+        });
+        store.save(&state).unwrap();
+
+        assert_eq!(store.load().unwrap(), state);
+        fs::remove_file(&path).ok();
+    }
+}
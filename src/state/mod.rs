@@ -0,0 +1,704 @@
+mod json;
+mod sqlite;
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use crate::config::{BotConfig, StateBackend};
+use crate::templates::template_id;
+
+pub use json::JsonStateStore;
+pub use sqlite::SqliteStateStore;
+
+/// Whether a tweet was an original post or a reply to someone else
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PostKind {
+    Original,
+    Reply,
+}
+
+impl PostKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostKind::Original => "original",
+            PostKind::Reply => "reply",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "original" => Some(PostKind::Original),
+            "reply" => Some(PostKind::Reply),
+            _ => None,
+        }
+    }
+}
+
+/// A tweet the bot has posted
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostRecord {
+    pub tweet_id: String,
+    pub kind: PostKind,
+    pub text: String,
+    pub in_reply_to: Option<String>,
+    pub posted_at: DateTime<Utc>,
+}
+
+/// Which template produced a scheduled post
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostOrigin {
+    /// Content category, e.g. `ai`, `meme` or `poll`
+    pub category: String,
+    /// `template_id` of the template's text
+    pub template: String,
+}
+
+impl PostOrigin {
+    /// Origin of a post made from the template with text `template`
+    pub fn new(category: &str, template: &str) -> Self {
+        Self {
+            category: category.to_string(),
+            template: template_id(template),
+        }
+    }
+}
+
+/// Public engagement counts for a tweet
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MetricCounts {
+    pub likes: u64,
+    pub reposts: u64,
+    pub replies: u64,
+    pub quotes: u64,
+    /// Only reported for some accounts and tweets
+    pub impressions: Option<u64>,
+}
+
+/// Engagements at which a post earns half of the full reward
+const REWARD_HALF_POINT: f64 = 10.0;
+
+impl MetricCounts {
+    /// Likes, reposts, replies and quotes together
+    pub fn engagements(&self) -> u64 {
+        self.likes + self.reposts + self.replies + self.quotes
+    }
+
+    /// Engagements squashed into 0..1, so one viral post can't drown out the rest
+    pub fn reward(&self) -> f64 {
+        let engagements = self.engagements() as f64;
+        engagements / (engagements + REWARD_HALF_POINT)
+    }
+}
+
+/// What the template selector has learned about one template
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ArmStats {
+    /// Posts made from this template
+    pub pulls: u32,
+    /// Sum of those posts' rewards
+    pub reward: f64,
+}
+
+/// Template stats per category, keyed by template ID
+pub type TemplateArms = BTreeMap<String, BTreeMap<String, ArmStats>>;
+
+impl ArmStats {
+    pub fn mean(&self) -> f64 {
+        if self.pulls == 0 {
+            0.0
+        } else {
+            self.reward / self.pulls as f64
+        }
+    }
+}
+
+/// How a scheduled post performed, kept with the template that produced it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostMetrics {
+    pub tweet_id: String,
+    pub origin: PostOrigin,
+    pub posted_at: DateTime<Utc>,
+    pub counts: MetricCounts,
+    /// When `counts` were last fetched; `None` until the first collection
+    pub collected_at: Option<DateTime<Utc>>,
+}
+
+/// Posts keep collecting metrics for this long; after that the counts are final
+const METRICS_WINDOW_DAYS: i64 = 7;
+
+/// Post counts for one period (a UTC day or a calendar month)
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PostCounter {
+    pub period: String,
+    pub originals: u32,
+    pub replies: u32,
+}
+
+impl PostCounter {
+    /// Count for `kind` in `period`, or 0 if the counter belongs to an older period
+    pub fn count(&self, period: &str, kind: PostKind) -> u32 {
+        if self.period != period {
+            return 0;
+        }
+        match kind {
+            PostKind::Original => self.originals,
+            PostKind::Reply => self.replies,
+        }
+    }
+
+    fn bump(&mut self, period: String, kind: PostKind) {
+        if self.period != period {
+            *self = PostCounter {
+                period,
+                ..Default::default()
+            };
+        }
+        match kind {
+            PostKind::Original => self.originals += 1,
+            PostKind::Reply => self.replies += 1,
+        }
+    }
+
+    /// Take back a post counted in `period`; older periods are left alone
+    fn unbump(&mut self, period: &str, kind: PostKind) {
+        if self.period != period {
+            return;
+        }
+        match kind {
+            PostKind::Original => self.originals = self.originals.saturating_sub(1),
+            PostKind::Reply => self.replies = self.replies.saturating_sub(1),
+        }
+    }
+}
+
+/// API read calls made in one calendar month
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ReadCounter {
+    pub period: String,
+    pub count: u32,
+}
+
+/// Direct-message replies sent in one UTC day
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DmCounter {
+    pub period: String,
+    pub count: u32,
+}
+
+/// A poll the bot posted whose results haven't been summarized yet
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PendingPoll {
+    pub tweet_id: String,
+    pub question: String,
+    pub closes_at: DateTime<Utc>,
+}
+
+/// How long engagements are remembered; recent search only reaches back 7 days
+const ENGAGEMENT_MEMORY_DAYS: i64 = 7;
+
+/// What the search job did with a tweet it found
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EngagementKind {
+    Like,
+    Quote,
+    Reply,
+}
+
+/// A tweet the search job engaged with
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EngagementRecord {
+    pub tweet_id: String,
+    pub kind: EngagementKind,
+    pub at: DateTime<Utc>,
+}
+
+/// How long follower actions are kept, for auditing and to avoid repeats
+const FOLLOWER_LOG_DAYS: i64 = 30;
+
+/// What the follower job did for a new follower
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowerActionKind {
+    Welcomed,
+    FollowedBack,
+}
+
+/// One welcome DM or follow-back
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FollowerAction {
+    pub user_id: String,
+    pub username: String,
+    pub kind: FollowerActionKind,
+    pub at: DateTime<Utc>,
+}
+
+/// Older mention pages a capped fetch left behind, picked up by the next check
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MentionBacklog {
+    /// Cursor the interrupted fetch started from
+    pub since_id: Option<String>,
+    pub next_token: String,
+    /// Newest mention of the interrupted fetch; the cursor moves here once
+    /// the backlog is drained
+    pub newest_id: Option<String>,
+}
+
+/// The account the bot's credentials belong to, as last verified with X
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Identity {
+    pub user_id: String,
+    pub username: String,
+    pub protected: bool,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// The `test_config` account, verified just now, for tests
+#[cfg(test)]
+pub fn test_identity() -> Identity {
+    Identity {
+        user_id: "1000".to_string(),
+        username: "test".to_string(),
+        protected: false,
+        verified_at: Utc::now(),
+    }
+}
+
+/// Everything the bot needs to remember across restarts
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BotState {
+    /// Newest mention ID already processed (the `since_id` cursor)
+    pub since_id: Option<String>,
+    /// Set while older mentions are still waiting behind the cursor
+    #[serde(default)]
+    pub mention_backlog: Option<MentionBacklog>,
+    #[serde(default)]
+    pub daily: PostCounter,
+    #[serde(default)]
+    pub monthly: PostCounter,
+    #[serde(default)]
+    pub posts: Vec<PostRecord>,
+    #[serde(default)]
+    pub monthly_reads: ReadCounter,
+    #[serde(default)]
+    pub pending_polls: Vec<PendingPoll>,
+    #[serde(default)]
+    pub identity: Option<Identity>,
+    /// Newest result already seen for each search query
+    #[serde(default)]
+    pub search_since_ids: BTreeMap<String, String>,
+    /// Engagements from the last week, oldest first
+    #[serde(default)]
+    pub engagements: Vec<EngagementRecord>,
+    /// Newest DM event already processed
+    #[serde(default)]
+    pub dm_since_id: Option<String>,
+    #[serde(default)]
+    pub dm_replies: DmCounter,
+    /// Follower IDs seen on the last follower check; `None` before the first
+    #[serde(default)]
+    pub follower_snapshot: Option<BTreeSet<String>>,
+    /// Follower actions from the last 30 days, oldest first
+    #[serde(default)]
+    pub follower_actions: Vec<FollowerAction>,
+    /// Engagement metrics for every scheduled post, oldest first. Only the
+    /// last 7 days' posts are still refreshed; older counts are final.
+    #[serde(default)]
+    pub post_metrics: Vec<PostMetrics>,
+}
+
+impl BotState {
+    /// Append a post to the history and bump the day/month counters
+    pub fn record_post(&mut self, record: PostRecord) {
+        self.daily.bump(day_key(&record.posted_at), record.kind);
+        self.monthly.bump(month_key(&record.posted_at), record.kind);
+        self.posts.push(record);
+    }
+
+    /// Drop a deleted post from the history and the counters it was bumped in
+    pub fn remove_post(&mut self, tweet_id: &str) {
+        let Some(index) = self.posts.iter().position(|p| p.tweet_id == tweet_id) else {
+            return;
+        };
+        let record = self.posts.remove(index);
+        self.daily.unbump(&day_key(&record.posted_at), record.kind);
+        self.monthly.unbump(&month_key(&record.posted_at), record.kind);
+    }
+
+    pub fn posts_on(&self, at: &DateTime<Utc>, kind: PostKind) -> u32 {
+        self.daily.count(&day_key(at), kind)
+    }
+
+    pub fn posts_in_month(&self, at: &DateTime<Utc>, kind: PostKind) -> u32 {
+        self.monthly.count(&month_key(at), kind)
+    }
+
+    /// Count one read call against this month's read budget
+    pub fn record_read(&mut self, at: &DateTime<Utc>) {
+        let period = month_key(at);
+        if self.monthly_reads.period != period {
+            self.monthly_reads = ReadCounter { period, count: 0 };
+        }
+        self.monthly_reads.count += 1;
+    }
+
+    /// Polls that have closed by `now` and still need a summary
+    pub fn closed_polls(&self, now: &DateTime<Utc>) -> Vec<PendingPoll> {
+        self.pending_polls
+            .iter()
+            .filter(|p| p.closes_at <= *now)
+            .cloned()
+            .collect()
+    }
+
+    pub fn remove_poll(&mut self, tweet_id: &str) {
+        self.pending_polls.retain(|p| p.tweet_id != tweet_id);
+    }
+
+    /// Log an engagement, forgetting ones older than search can surface again
+    pub fn record_engagement(&mut self, record: EngagementRecord) {
+        let cutoff = record.at - chrono::Duration::days(ENGAGEMENT_MEMORY_DAYS);
+        self.engagements.retain(|e| e.at > cutoff);
+        self.engagements.push(record);
+    }
+
+    pub fn engagements_on(&self, at: &DateTime<Utc>) -> u32 {
+        let day = day_key(at);
+        self.engagements.iter().filter(|e| day_key(&e.at) == day).count() as u32
+    }
+
+    pub fn has_engaged(&self, tweet_id: &str) -> bool {
+        self.engagements.iter().any(|e| e.tweet_id == tweet_id)
+    }
+
+    /// Start collecting metrics for a newly posted tweet
+    pub fn track_post(&mut self, tweet_id: &str, origin: PostOrigin, posted_at: DateTime<Utc>) {
+        self.post_metrics.push(PostMetrics {
+            tweet_id: tweet_id.to_string(),
+            origin,
+            posted_at,
+            counts: MetricCounts::default(),
+            collected_at: None,
+        });
+    }
+
+    /// Tracked posts still inside the collection window at `now`
+    pub fn metrics_due(&self, now: &DateTime<Utc>) -> Vec<String> {
+        let cutoff = *now - chrono::Duration::days(METRICS_WINDOW_DAYS);
+        self.post_metrics
+            .iter()
+            .filter(|m| m.posted_at > cutoff)
+            .map(|m| m.tweet_id.clone())
+            .collect()
+    }
+
+    /// Store fresh counts for a tracked post, replacing any earlier ones
+    pub fn record_metrics(&mut self, tweet_id: &str, counts: MetricCounts, at: DateTime<Utc>) {
+        if let Some(metrics) = self.post_metrics.iter_mut().find(|m| m.tweet_id == tweet_id) {
+            metrics.counts = counts;
+            metrics.collected_at = Some(at);
+        }
+    }
+
+    /// What the template selector works from, built from every tracked
+    /// post. Each one counts as a pull from the moment it's posted, so the
+    /// next post doesn't repeat a template whose metrics are still out, and
+    /// one that is never measured stays a pull with no reward. A post's
+    /// reward comes from its latest counts.
+    pub fn template_stats(&self) -> TemplateArms {
+        let mut arms = TemplateArms::new();
+        for metrics in &self.post_metrics {
+            let arm = arms
+                .entry(metrics.origin.category.clone())
+                .or_default()
+                .entry(metrics.origin.template.clone())
+                .or_default();
+            arm.pulls += 1;
+            arm.reward += metrics.counts.reward();
+        }
+        arms
+    }
+
+    /// Log a follower action, dropping ones past the retention window
+    pub fn record_follower_action(&mut self, action: FollowerAction) {
+        let cutoff = action.at - chrono::Duration::days(FOLLOWER_LOG_DAYS);
+        self.follower_actions.retain(|a| a.at > cutoff);
+        self.follower_actions.push(action);
+    }
+
+    pub fn follower_actions_on(&self, at: &DateTime<Utc>) -> u32 {
+        let day = day_key(at);
+        self.follower_actions
+            .iter()
+            .filter(|a| day_key(&a.at) == day)
+            .count() as u32
+    }
+
+    pub fn has_follower_action(&self, user_id: &str, kind: FollowerActionKind) -> bool {
+        self.follower_actions
+            .iter()
+            .any(|a| a.user_id == user_id && a.kind == kind)
+    }
+
+    pub fn record_dm_reply(&mut self, at: &DateTime<Utc>) {
+        let period = day_key(at);
+        if self.dm_replies.period != period {
+            self.dm_replies = DmCounter { period, count: 0 };
+        }
+        self.dm_replies.count += 1;
+    }
+
+    pub fn dm_replies_on(&self, at: &DateTime<Utc>) -> u32 {
+        if self.dm_replies.period == day_key(at) {
+            self.dm_replies.count
+        } else {
+            0
+        }
+    }
+
+    pub fn reads_in_month(&self, at: &DateTime<Utc>) -> u32 {
+        if self.monthly_reads.period == month_key(at) {
+            self.monthly_reads.count
+        } else {
+            0
+        }
+    }
+}
+
+pub fn day_key(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d").to_string()
+}
+
+pub fn month_key(at: &DateTime<Utc>) -> String {
+    at.format("%Y-%m").to_string()
+}
+
+/// Midnight UTC on the first day of `at`'s month
+pub fn month_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let date = NaiveDate::from_ymd_opt(at.year(), at.month(), 1).unwrap();
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Midnight UTC on the first day of the month after `at`
+pub fn next_month_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if at.month() == 12 {
+        (at.year() + 1, 1)
+    } else {
+        (at.year(), at.month() + 1)
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Storage backend for `BotState`
+pub trait StateStore: Send + Sync {
+    /// Load the saved state, or a default state if nothing has been saved yet
+    fn load(&self) -> Result<BotState>;
+
+    /// Persist the full state
+    fn save(&self, state: &BotState) -> Result<()>;
+}
+
+/// Open the store selected by `STATE_BACKEND`
+pub fn open_store(config: &BotConfig) -> Result<Box<dyn StateStore>> {
+    Ok(match config.state_backend {
+        StateBackend::Json => Box::new(JsonStateStore::new(&config.state_path)),
+        StateBackend::Sqlite => Box::new(SqliteStateStore::open(&config.state_path)?),
+    })
+}
+
+/// In-memory state backed by a store; every update is written through
+pub struct StateHandle {
+    store: Box<dyn StateStore>,
+    state: Mutex<BotState>,
+}
+
+impl StateHandle {
+    pub fn open(store: Box<dyn StateStore>) -> Result<Self> {
+        let state = store.load()?;
+        Ok(Self {
+            store,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn read<R>(&self, f: impl FnOnce(&BotState) -> R) -> R {
+        let state = self.state.lock().unwrap();
+        f(&state)
+    }
+
+    /// Apply `f` to the state and save the result
+    pub fn update(&self, f: impl FnOnce(&mut BotState)) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        f(&mut state);
+        self.store.save(&state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, kind: PostKind, at: DateTime<Utc>) -> PostRecord {
+        PostRecord {
+            tweet_id: id.to_string(),
+            kind,
+            text: format!("tweet {}", id),
+            in_reply_to: None,
+            posted_at: at,
+        }
+    }
+
+    #[test]
+    fn test_counters_roll_over() {
+        let mut state = BotState::default();
+        let day1 = Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap();
+        let day2 = Utc.with_ymd_and_hms(2025, 2, 1, 12, 0, 0).unwrap();
+
+        state.record_post(record("1", PostKind::Original, day1));
+        state.record_post(record("2", PostKind::Reply, day1));
+        state.record_post(record("3", PostKind::Original, day1));
+        assert_eq!(state.posts_on(&day1, PostKind::Original), 2);
+        assert_eq!(state.posts_on(&day1, PostKind::Reply), 1);
+
+        state.record_post(record("4", PostKind::Original, day2));
+        assert_eq!(state.posts_on(&day2, PostKind::Original), 1);
+        assert_eq!(state.posts_on(&day1, PostKind::Original), 0);
+        assert_eq!(state.posts_in_month(&day2, PostKind::Original), 1);
+        assert_eq!(state.posts.len(), 4);
+    }
+
+    #[test]
+    fn test_remove_post_takes_back_its_count() {
+        let mut state = BotState::default();
+        let day1 = Utc.with_ymd_and_hms(2025, 1, 30, 12, 0, 0).unwrap();
+        let day2 = Utc.with_ymd_and_hms(2025, 1, 31, 12, 0, 0).unwrap();
+
+        state.record_post(record("1", PostKind::Original, day1));
+        state.record_post(record("2", PostKind::Original, day2));
+        state.record_post(record("3", PostKind::Reply, day2));
+
+        state.remove_post("2");
+        state.remove_post("1");
+        state.remove_post("404");
+        assert_eq!(state.posts_on(&day2, PostKind::Original), 0);
+        assert_eq!(state.posts_on(&day2, PostKind::Reply), 1);
+        assert_eq!(state.posts_in_month(&day2, PostKind::Original), 0);
+        assert_eq!(state.posts.len(), 1);
+    }
+
+    #[test]
+    fn test_closed_polls() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 2, 12, 0, 0).unwrap();
+        let poll = |id: &str, hours: i64| PendingPoll {
+            tweet_id: id.to_string(),
+            question: "Which pattern?".to_string(),
+            closes_at: now + chrono::Duration::hours(hours),
+        };
+        let mut state = BotState {
+            pending_polls: vec![poll("1", -1), poll("2", 3)],
+            ..Default::default()
+        };
+
+        let closed = state.closed_polls(&now);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].tweet_id, "1");
+
+        state.remove_poll("1");
+        assert!(state.closed_polls(&now).is_empty());
+        assert_eq!(state.pending_polls.len(), 1);
+    }
+
+    #[test]
+    fn test_metrics_collected_within_window() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 10, 12, 0, 0).unwrap();
+        let mut state = BotState::default();
+        state.track_post("1", PostOrigin::new("ai", "Agent swarms"), now - chrono::Duration::days(8));
+        state.track_post("2", PostOrigin::new("poll", "Which pattern?"), now - chrono::Duration::hours(5));
+
+        assert_eq!(state.metrics_due(&now), vec!["2".to_string()]);
+
+        let counts = MetricCounts {
+            likes: 4,
+            impressions: Some(250),
+            ..Default::default()
+        };
+        state.record_metrics("2", counts.clone(), now);
+        assert_eq!(state.post_metrics[1].counts, counts);
+        assert_eq!(state.post_metrics[1].collected_at, Some(now));
+        assert!(state.post_metrics[0].collected_at.is_none());
+    }
+
+    #[test]
+    fn test_later_metrics_replace_a_posts_reward() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 10, 12, 0, 0).unwrap();
+        let mut state = BotState::default();
+        state.track_post("1", PostOrigin::new("ai", "Agent swarms"), now - chrono::Duration::hours(6));
+        state.track_post("2", PostOrigin::new("ai", "Agent swarms"), now - chrono::Duration::hours(5));
+
+        let likes = |likes| MetricCounts {
+            likes,
+            ..Default::default()
+        };
+        // Posted but not measured yet: pulls without reward
+        let arm = state.template_stats()["ai"][&template_id("Agent swarms")];
+        assert_eq!(arm.pulls, 2);
+        assert_eq!(arm.reward, 0.0);
+
+        state.record_metrics("1", likes(2), now);
+        state.record_metrics("1", likes(10), now);
+        state.record_metrics("2", likes(30), now);
+
+        let arm = state.template_stats()["ai"][&template_id("Agent swarms")];
+        assert_eq!(arm.pulls, 2);
+        assert!((arm.mean() - (0.5 + 0.75) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unmeasured_posts_stay_pulls_past_the_window() {
+        let now = Utc.with_ymd_and_hms(2025, 4, 10, 12, 0, 0).unwrap();
+        let mut state = BotState::default();
+        state.track_post("1", PostOrigin::new("ai", "Agent swarms"), now - chrono::Duration::days(9));
+        state.track_post("2", PostOrigin::new("ai", "Agent swarms"), now - chrono::Duration::days(8));
+        state.record_metrics(
+            "2",
+            MetricCounts {
+                likes: 10,
+                ..Default::default()
+            },
+            now - chrono::Duration::days(2),
+        );
+
+        // Neither is refreshed any more, but both still count
+        assert!(state.metrics_due(&now).is_empty());
+        let arm = state.template_stats()["ai"][&template_id("Agent swarms")];
+        assert_eq!(arm.pulls, 2);
+        assert!((arm.mean() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_engagements_expire_after_a_week() {
+        let day1 = Utc.with_ymd_and_hms(2025, 4, 1, 12, 0, 0).unwrap();
+        let engagement = |id: &str, at: DateTime<Utc>| EngagementRecord {
+            tweet_id: id.to_string(),
+            kind: EngagementKind::Like,
+            at,
+        };
+        let mut state = BotState::default();
+
+        state.record_engagement(engagement("1", day1));
+        state.record_engagement(engagement("2", day1));
+        assert_eq!(state.engagements_on(&day1), 2);
+        assert!(state.has_engaged("1"));
+
+        let day9 = day1 + chrono::Duration::days(8);
+        state.record_engagement(engagement("3", day9));
+        assert_eq!(state.engagements_on(&day9), 1);
+        assert!(!state.has_engaged("1"));
+        assert_eq!(state.engagements.len(), 1);
+    }
+}
@@ -0,0 +1,365 @@
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::{BotState, MetricCounts, PostKind, PostMetrics, PostOrigin, PostRecord, StateStore};
+
+/// Stores scalar state in a key/value table; the post log and post metrics get tables of their own
+pub struct SqliteStateStore {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStateStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS bot_state (
+                 key   TEXT PRIMARY KEY,
+                 value TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS posts (
+                 tweet_id    TEXT PRIMARY KEY,
+                 kind        TEXT NOT NULL,
+                 text        TEXT NOT NULL,
+                 in_reply_to TEXT,
+                 posted_at   TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS post_metrics (
+                 tweet_id     TEXT PRIMARY KEY,
+                 category     TEXT NOT NULL,
+                 template     TEXT NOT NULL,
+                 posted_at    TEXT NOT NULL,
+                 likes        INTEGER NOT NULL,
+                 reposts      INTEGER NOT NULL,
+                 replies      INTEGER NOT NULL,
+                 quotes       INTEGER NOT NULL,
+                 impressions  INTEGER,
+                 collected_at TEXT
+             );",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+fn get_value(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM bot_state WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn set_value(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO bot_state (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+impl StateStore for SqliteStateStore {
+    fn load(&self) -> Result<BotState> {
+        let conn = self.conn.lock().unwrap();
+        let mut state = BotState {
+            since_id: get_value(&conn, "since_id")?,
+            ..Default::default()
+        };
+        if let Some(backlog) = get_value(&conn, "mention_backlog")? {
+            state.mention_backlog = Some(serde_json::from_str(&backlog)?);
+        }
+        if let Some(daily) = get_value(&conn, "daily")? {
+            state.daily = serde_json::from_str(&daily)?;
+        }
+        if let Some(monthly) = get_value(&conn, "monthly")? {
+            state.monthly = serde_json::from_str(&monthly)?;
+        }
+        if let Some(reads) = get_value(&conn, "monthly_reads")? {
+            state.monthly_reads = serde_json::from_str(&reads)?;
+        }
+        if let Some(polls) = get_value(&conn, "pending_polls")? {
+            state.pending_polls = serde_json::from_str(&polls)?;
+        }
+        if let Some(identity) = get_value(&conn, "identity")? {
+            state.identity = Some(serde_json::from_str(&identity)?);
+        }
+        if let Some(since_ids) = get_value(&conn, "search_since_ids")? {
+            state.search_since_ids = serde_json::from_str(&since_ids)?;
+        }
+        if let Some(engagements) = get_value(&conn, "engagements")? {
+            state.engagements = serde_json::from_str(&engagements)?;
+        }
+        state.dm_since_id = get_value(&conn, "dm_since_id")?;
+        if let Some(dm_replies) = get_value(&conn, "dm_replies")? {
+            state.dm_replies = serde_json::from_str(&dm_replies)?;
+        }
+        if let Some(snapshot) = get_value(&conn, "follower_snapshot")? {
+            state.follower_snapshot = Some(serde_json::from_str(&snapshot)?);
+        }
+        if let Some(actions) = get_value(&conn, "follower_actions")? {
+            state.follower_actions = serde_json::from_str(&actions)?;
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT tweet_id, kind, text, in_reply_to, posted_at FROM posts ORDER BY posted_at",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (tweet_id, kind, text, in_reply_to, posted_at) = row?;
+            state.posts.push(PostRecord {
+                tweet_id,
+                kind: PostKind::parse(&kind).ok_or_else(|| anyhow!("Unknown post kind '{}'", kind))?,
+                text,
+                in_reply_to,
+                posted_at: posted_at.parse()?,
+            });
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT tweet_id, category, template, posted_at, likes, reposts, replies, quotes,
+                    impressions, collected_at
+             FROM post_metrics ORDER BY posted_at",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                [
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, i64>(6)?,
+                    row.get::<_, i64>(7)?,
+                ],
+                row.get::<_, Option<i64>>(8)?,
+                row.get::<_, Option<String>>(9)?,
+            ))
+        })?;
+        for row in rows {
+            let (
+                tweet_id,
+                category,
+                template,
+                posted_at,
+                [likes, reposts, replies, quotes],
+                impressions,
+                collected_at,
+            ) = row?;
+            state.post_metrics.push(PostMetrics {
+                tweet_id,
+                origin: PostOrigin { category, template },
+                posted_at: posted_at.parse()?,
+                counts: MetricCounts {
+                    likes: likes as u64,
+                    reposts: reposts as u64,
+                    replies: replies as u64,
+                    quotes: quotes as u64,
+                    impressions: impressions.map(|i| i as u64),
+                },
+                collected_at: collected_at.map(|at| at.parse()).transpose()?,
+            });
+        }
+
+        Ok(state)
+    }
+
+    fn save(&self, state: &BotState) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        match &state.since_id {
+            Some(id) => set_value(&tx, "since_id", id)?,
+            None => {
+                tx.execute("DELETE FROM bot_state WHERE key = 'since_id'", [])?;
+            }
+        }
+        match &state.mention_backlog {
+            Some(backlog) => set_value(&tx, "mention_backlog", &serde_json::to_string(backlog)?)?,
+            None => {
+                tx.execute("DELETE FROM bot_state WHERE key = 'mention_backlog'", [])?;
+            }
+        }
+        set_value(&tx, "daily", &serde_json::to_string(&state.daily)?)?;
+        set_value(&tx, "monthly", &serde_json::to_string(&state.monthly)?)?;
+        set_value(&tx, "monthly_reads", &serde_json::to_string(&state.monthly_reads)?)?;
+        set_value(&tx, "pending_polls", &serde_json::to_string(&state.pending_polls)?)?;
+        match &state.identity {
+            Some(identity) => set_value(&tx, "identity", &serde_json::to_string(identity)?)?,
+            None => {
+                tx.execute("DELETE FROM bot_state WHERE key = 'identity'", [])?;
+            }
+        }
+        set_value(&tx, "search_since_ids", &serde_json::to_string(&state.search_since_ids)?)?;
+        set_value(&tx, "engagements", &serde_json::to_string(&state.engagements)?)?;
+        match &state.dm_since_id {
+            Some(id) => set_value(&tx, "dm_since_id", id)?,
+            None => {
+                tx.execute("DELETE FROM bot_state WHERE key = 'dm_since_id'", [])?;
+            }
+        }
+        set_value(&tx, "dm_replies", &serde_json::to_string(&state.dm_replies)?)?;
+        match &state.follower_snapshot {
+            Some(snapshot) => set_value(&tx, "follower_snapshot", &serde_json::to_string(snapshot)?)?,
+            None => {
+                tx.execute("DELETE FROM bot_state WHERE key = 'follower_snapshot'", [])?;
+            }
+        }
+        set_value(&tx, "follower_actions", &serde_json::to_string(&state.follower_actions)?)?;
+
+        // Posts only leave the log when deleted; everything else is written once
+        let saved: BTreeSet<String> = tx
+            .prepare("SELECT tweet_id FROM posts")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        let logged: BTreeSet<&str> = state.posts.iter().map(|p| p.tweet_id.as_str()).collect();
+        for tweet_id in saved.iter().filter(|id| !logged.contains(id.as_str())) {
+            tx.execute("DELETE FROM posts WHERE tweet_id = ?1", params![tweet_id])?;
+        }
+        for post in state.posts.iter().filter(|p| !saved.contains(&p.tweet_id)) {
+            tx.execute(
+                "INSERT OR IGNORE INTO posts (tweet_id, kind, text, in_reply_to, posted_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    post.tweet_id,
+                    post.kind.as_str(),
+                    post.text,
+                    post.in_reply_to,
+                    post.posted_at.to_rfc3339(),
+                ],
+            )?;
+        }
+
+        for metrics in &state.post_metrics {
+            tx.execute(
+                "INSERT INTO post_metrics (tweet_id, category, template, posted_at, likes, reposts,
+                                           replies, quotes, impressions, collected_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT(tweet_id) DO UPDATE SET
+                     likes = excluded.likes,
+                     reposts = excluded.reposts,
+                     replies = excluded.replies,
+                     quotes = excluded.quotes,
+                     impressions = excluded.impressions,
+                     collected_at = excluded.collected_at",
+                params![
+                    metrics.tweet_id,
+                    metrics.origin.category,
+                    metrics.origin.template,
+                    metrics.posted_at.to_rfc3339(),
+                    metrics.counts.likes as i64,
+                    metrics.counts.reposts as i64,
+                    metrics.counts.replies as i64,
+                    metrics.counts.quotes as i64,
+                    metrics.counts.impressions.map(|i| i as i64),
+                    metrics.collected_at.map(|at| at.to_rfc3339()),
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{EngagementKind, EngagementRecord, Identity};
+    use chrono::Utc;
+
+    #[test]
+    fn test_round_trip() {
+        let store = SqliteStateStore::in_memory().unwrap();
+        assert_eq!(store.load().unwrap(), BotState::default());
+
+        let mut state = BotState {
+            since_id: Some("42".to_string()),
+            dm_since_id: Some("900".to_string()),
+            follower_snapshot: Some(["7".to_string(), "8".to_string()].into()),
+            identity: Some(Identity {
+                user_id: "1000".to_string(),
+                username: "agentropic".to_string(),
+                protected: false,
+                verified_at: "2025-04-02T12:00:00Z".parse().unwrap(),
+            }),
+            ..Default::default()
+        };
+        state.record_post(PostRecord {
+            tweet_id: "100".to_string(),
+            kind: PostKind::Reply,
+            text: "hello".to_string(),
+            in_reply_to: Some("99".to_string()),
+            posted_at: Utc::now(),
+        });
+        state.record_read(&Utc::now());
+        state.record_dm_reply(&Utc::now());
+        state.track_post("100", PostOrigin::new("meme", "lol"), "2025-04-02T12:00:00Z".parse().unwrap());
+        state.record_metrics(
+            "100",
+            MetricCounts {
+                likes: 12,
+                reposts: 2,
+                impressions: Some(900),
+                ..Default::default()
+            },
+            "2025-04-03T12:00:00Z".parse().unwrap(),
+        );
+        state
+            .search_since_ids
+            .insert("agentropic".to_string(), "77".to_string());
+        state.record_engagement(EngagementRecord {
+            tweet_id: "77".to_string(),
+            kind: EngagementKind::Quote,
+            at: "2025-04-02T12:00:00Z".parse().unwrap(),
+        });
+        store.save(&state).unwrap();
+        // Saving twice must not duplicate the log
+        store.save(&state).unwrap();
+
+        assert_eq!(store.load().unwrap(), state);
+    }
+
+    #[test]
+    fn test_deleted_posts_leave_the_table() {
+        let store = SqliteStateStore::in_memory().unwrap();
+        let mut state = BotState::default();
+        for id in ["100", "101"] {
+            state.record_post(PostRecord {
+                tweet_id: id.to_string(),
+                kind: PostKind::Original,
+                text: format!("tweet {}", id),
+                in_reply_to: None,
+                posted_at: Utc::now(),
+            });
+        }
+        store.save(&state).unwrap();
+
+        state.remove_post("100");
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap(), state);
+        assert_eq!(state.posts.len(), 1);
+    }
+}
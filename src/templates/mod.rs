@@ -1,7 +1,15 @@
-﻿use rand::seq::SliceRandom;
+﻿use sha2::{Digest, Sha256};
 
 pub struct TweetTemplates;
 
+/// A poll post: question text, 2-4 options (25 chars max each) and how long it stays open
+#[derive(Debug, Clone, Copy)]
+pub struct PollTemplate {
+    pub question: &'static str,
+    pub options: &'static [&'static str],
+    pub duration_minutes: u32,
+}
+
 // All templates must be ≤250 chars to leave room for the ~30 char signature.
 // The generator will skip the signature if needed, but we aim to always fit.
 
@@ -61,40 +69,56 @@ impl TweetTemplates {
         ]
     }
 
-    pub fn random_ai_tweet() -> String {
-        Self::ai_templates()
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string()
-    }
-
-    pub fn random_agentropic_tweet() -> String {
-        Self::agentropic_templates()
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string()
-    }
-
-    pub fn random_crypto_tweet() -> String {
-        Self::crypto_ai_templates()
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string()
+    /// Community polls
+    pub fn poll_templates() -> Vec<PollTemplate> {
+        vec![
+            PollTemplate {
+                question: "Which Agentropic pattern do you use most?\n\n#Agentropic #MultiAgent",
+                options: &["Hierarchy", "Swarm", "Market", "Team"],
+                duration_minutes: 1440,
+            },
+            PollTemplate {
+                question: "What's the hardest part of building multi-agent systems?\n\n#AI #Agents",
+                options: &["Coordination", "Fault tolerance", "Messaging", "Debugging"],
+                duration_minutes: 1440,
+            },
+            PollTemplate {
+                question: "What language are you building your agents in?\n\n#AI #Agents",
+                options: &["Rust", "Python", "TypeScript", "Something else"],
+                duration_minutes: 1440,
+            },
+            PollTemplate {
+                question: "How should agents decide together?\n\n#Agentropic #MultiAgent",
+                options: &["Auctions", "Voting", "A leader decides", "Swarm consensus"],
+                duration_minutes: 2880,
+            },
+        ]
     }
 
-    pub fn random_meme_tweet() -> String {
-        Self::meme_ai_templates()
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string()
+    /// Diagrams that can ride along with posts mentioning a pattern:
+    /// (pattern name as written in templates, image file under MEDIA_DIR, alt text)
+    pub fn pattern_diagrams() -> Vec<(&'static str, &'static str, &'static str)> {
+        vec![
+            ("Hierarchy", "hierarchy.png", "Agentropic hierarchy pattern: a manager agent delegating tasks to worker agents, which report results back up."),
+            ("Swarm", "swarm.png", "Agentropic swarm pattern: many peer agents sharing local signals and converging on a decision without a leader."),
+            ("Market", "market.png", "Agentropic market pattern: buyer and seller agents exchanging bids through an auctioneer."),
+            ("Coalition", "coalition.png", "Agentropic coalition pattern: agents forming temporary groups around a shared goal."),
+            ("Team", "team.png", "Agentropic team pattern: agents with fixed roles working toward one shared plan."),
+            ("Holarchy", "holarchy.png", "Agentropic holarchy pattern: nested agent groups, each acting as a single agent to the level above."),
+            ("Federation", "federation.png", "Agentropic federation pattern: independent agent groups connected through facilitator agents."),
+            ("Blackboard", "blackboard.png", "Agentropic blackboard pattern: specialist agents reading and writing a shared workspace."),
+            ("BDI", "bdi.png", "BDI loop: beliefs update desires, desires are filtered into intentions, intentions drive actions."),
+        ]
     }
+}
 
-    pub fn random_bull_tweet() -> String {
-        Self::general_bull_templates()
-            .choose(&mut rand::thread_rng())
-            .unwrap()
-            .to_string()
-    }
+/// Stable key for a template: the start of its text's SHA-256, so what the
+/// bot learned about a template survives others being added or reordered
+pub fn template_id(text: &str) -> String {
+    Sha256::digest(text.as_bytes())[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
 
 #[cfg(test)]
@@ -127,4 +151,37 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// Polls must satisfy the API's option and duration limits
+    #[test]
+    fn test_poll_templates_valid() {
+        for poll in TweetTemplates::poll_templates() {
+            assert!(poll.question.len() <= 280, "Question too long: {:?}", poll.question);
+            assert!((2..=4).contains(&poll.options.len()), "Bad option count: {:?}", poll.question);
+            assert!(poll.options.iter().all(|o| !o.is_empty() && o.chars().count() <= 25));
+            assert!((5..=10080).contains(&poll.duration_minutes));
+        }
+    }
+
+    /// Template stats are keyed by ID, so two templates must never share one
+    #[test]
+    fn test_template_ids_unique() {
+        let polls = TweetTemplates::poll_templates();
+        let all_templates = [
+            TweetTemplates::ai_templates(),
+            TweetTemplates::agentropic_templates(),
+            TweetTemplates::crypto_ai_templates(),
+            TweetTemplates::meme_ai_templates(),
+            TweetTemplates::general_bull_templates(),
+            polls.iter().map(|p| p.question).collect(),
+        ];
+
+        for templates in all_templates {
+            let ids: std::collections::BTreeSet<String> =
+                templates.iter().map(|t| template_id(t)).collect();
+            assert_eq!(ids.len(), templates.len());
+        }
+        assert_eq!(template_id("Agent swarms"), template_id("Agent swarms"));
+        assert_eq!(template_id("Agent swarms").len(), 16);
+    }
+}
@@ -0,0 +1,83 @@
+/// Maximum tweet length (Twitter limit)
+const MAX_TWEET_LENGTH: usize = 280;
+
+/// Room left for the " 12/34" counter appended to each part
+const COUNTER_RESERVE: usize = 8;
+
+/// Split text into tweets, numbering the parts ("1/3") when it needs more than one.
+///
+/// Breaks on spaces where possible so words stay whole; a single word longer
+/// than a tweet is cut at a character boundary.
+pub fn split_thread(text: &str) -> Vec<String> {
+    let text = text.trim();
+    if text.len() <= MAX_TWEET_LENGTH {
+        return vec![text.to_string()];
+    }
+
+    let budget = MAX_TWEET_LENGTH - COUNTER_RESERVE;
+    let mut parts: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split(' ').filter(|w| !w.is_empty()) {
+        let needed = if current.is_empty() { word.len() } else { current.len() + 1 + word.len() };
+        if needed <= budget {
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+            continue;
+        }
+
+        if !current.is_empty() {
+            parts.push(std::mem::take(&mut current));
+        }
+        let mut rest = word;
+        while rest.len() > budget {
+            let mut cut = budget;
+            while !rest.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            parts.push(rest[..cut].to_string());
+            rest = &rest[cut..];
+        }
+        current.push_str(rest);
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    let total = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(i, part)| format!("{} {}/{}", part.trim_end(), i + 1, total))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_text_is_single_part() {
+        assert_eq!(split_thread("hello agents"), vec!["hello agents".to_string()]);
+    }
+
+    #[test]
+    fn test_long_text_is_numbered() {
+        let text = "agents ".repeat(100);
+        let parts = split_thread(&text);
+        assert_eq!(parts.len(), 3);
+        assert!(parts[0].ends_with(" 1/3"));
+        assert!(parts[2].ends_with(" 3/3"));
+        assert!(parts.iter().all(|p| p.len() <= MAX_TWEET_LENGTH));
+        assert!(parts.iter().all(|p| !p.contains("agen ")));
+    }
+
+    #[test]
+    fn test_long_word_is_cut() {
+        let parts = split_thread(&"é".repeat(300));
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|p| p.len() <= MAX_TWEET_LENGTH));
+    }
+}
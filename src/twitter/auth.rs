@@ -0,0 +1,344 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use percent_encoding::percent_decode_str;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, DATE};
+use reqwest::{Client, Request};
+use sha1::Sha1;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use super::{percent_encode, TwitterError};
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Skew changes smaller than this are put down to latency and the one-second
+/// resolution of `Date`, and leave the correction alone
+const SKEW_TOLERANCE_SECS: i64 = 5;
+
+/// Boxed future returned by `Authenticator::refresh`, which keeps the trait object-safe
+pub type RefreshFuture<'a> = Pin<Box<dyn Future<Output = Result<bool, TwitterError>> + Send + 'a>>;
+
+/// Source of the `Authorization` header for every API request
+pub trait Authenticator: Send + Sync {
+    /// Set the `Authorization` header on a fully built request
+    fn authorize(&self, request: &mut Request) -> Result<(), TwitterError>;
+
+    /// Renew credentials that are about to expire, or unconditionally when
+    /// `force` is set after a 401. Resolves to whether new credentials are in
+    /// place, i.e. whether the request is worth re-sending.
+    fn refresh<'a>(&'a self, _http: &'a Client, _force: bool) -> RefreshFuture<'a> {
+        Box::pin(async { Ok(false) })
+    }
+
+    /// Line signing timestamps up with the server clock in a response's
+    /// `Date` header. Returns whether the correction moved, i.e. whether a
+    /// rejected request was likely signed with a stale timestamp.
+    fn sync_clock(&self, _headers: &HeaderMap) -> bool {
+        false
+    }
+
+    /// Credential values to scrub from recorded traffic
+    fn secrets(&self) -> Vec<String>;
+}
+
+/// Replace the request's `Authorization` header with `value`
+pub fn set_authorization(request: &mut Request, value: &str) -> Result<(), TwitterError> {
+    let value = HeaderValue::from_str(value).map_err(|e| TwitterError::Signing(e.to_string()))?;
+    request.headers_mut().insert(AUTHORIZATION, value);
+    Ok(())
+}
+
+/// OAuth 1.0a user-context signing with long-lived app and access tokens.
+///
+/// Signs whatever ends up in the built request: every query pair, repeated
+/// keys included, and the fields of a form-encoded body. Timestamps follow
+/// the server clock once a response has shown the local one to be off.
+pub struct OAuth1Signer {
+    consumer_key: String,
+    consumer_secret: String,
+    access_token: String,
+    access_token_secret: String,
+    clock: Box<dyn Fn() -> i64 + Send + Sync>,
+    nonce: Box<dyn Fn() -> String + Send + Sync>,
+    /// Seconds to add to `clock` to match the server
+    skew: AtomicI64,
+}
+
+impl OAuth1Signer {
+    pub fn new(
+        consumer_key: String,
+        consumer_secret: String,
+        access_token: String,
+        access_token_secret: String,
+    ) -> Self {
+        Self {
+            consumer_key,
+            consumer_secret,
+            access_token,
+            access_token_secret,
+            clock: Box::new(|| Utc::now().timestamp()),
+            nonce: Box::new(|| rand::random::<u64>().to_string()),
+            skew: AtomicI64::new(0),
+        }
+    }
+
+    /// Take `oauth_timestamp` from `clock` instead of the system time
+    #[cfg(test)]
+    pub fn with_clock(mut self, clock: impl Fn() -> i64 + Send + Sync + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Take `oauth_nonce` from `nonce` instead of a random number
+    #[cfg(test)]
+    pub fn with_nonce(mut self, nonce: impl Fn() -> String + Send + Sync + 'static) -> Self {
+        self.nonce = Box::new(nonce);
+        self
+    }
+
+    /// `Authorization` header value for `request`
+    pub fn header_for(&self, request: &Request) -> Result<String, TwitterError> {
+        let timestamp = ((self.clock)() + self.skew.load(Ordering::Relaxed)).to_string();
+        let nonce = (self.nonce)();
+        let mut oauth_params = vec![
+            ("oauth_consumer_key", self.consumer_key.clone()),
+            ("oauth_nonce", nonce),
+            ("oauth_signature_method", "HMAC-SHA1".to_string()),
+            ("oauth_timestamp", timestamp),
+            ("oauth_token", self.access_token.clone()),
+            ("oauth_version", "1.0".to_string()),
+        ];
+
+        let mut params = request_params(request);
+        params.extend(oauth_params.iter().map(|(k, v)| (k.to_string(), v.clone())));
+
+        let mut url = request.url().clone();
+        url.set_query(None);
+        url.set_fragment(None);
+        let signature = oauth_signature(
+            request.method().as_str(),
+            url.as_str(),
+            &params,
+            &self.consumer_secret,
+            &self.access_token_secret,
+        )?;
+
+        oauth_params.push(("oauth_signature", signature));
+        oauth_params.sort();
+        let fields = oauth_params
+            .iter()
+            .map(|(k, v)| format!(r#"{}="{}""#, k, percent_encode(v)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(format!("OAuth {}", fields))
+    }
+}
+
+impl Authenticator for OAuth1Signer {
+    fn authorize(&self, request: &mut Request) -> Result<(), TwitterError> {
+        let header = self.header_for(request)?;
+        set_authorization(request, &header)
+    }
+
+    fn sync_clock(&self, headers: &HeaderMap) -> bool {
+        let Some(server_time) = server_time(headers) else {
+            return false;
+        };
+        let skew = server_time.timestamp() - (self.clock)();
+        if (skew - self.skew.load(Ordering::Relaxed)).abs() <= SKEW_TOLERANCE_SECS {
+            return false;
+        }
+        self.skew.store(skew, Ordering::Relaxed);
+        true
+    }
+
+    fn secrets(&self) -> Vec<String> {
+        vec![
+            self.consumer_key.clone(),
+            self.consumer_secret.clone(),
+            self.access_token.clone(),
+            self.access_token_secret.clone(),
+        ]
+    }
+}
+
+/// Server time from an HTTP `Date` header, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+fn server_time(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    let value = headers.get(DATE)?.to_str().ok()?;
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// Query pairs plus form-encoded body fields, decoded, in request order
+fn request_params(request: &Request) -> Vec<(String, String)> {
+    let mut params: Vec<(String, String)> = request.url().query_pairs().into_owned().collect();
+
+    let is_form = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.starts_with("application/x-www-form-urlencoded"))
+        .unwrap_or(false);
+    if let Some(body) = request.body().and_then(|b| b.as_bytes()).filter(|_| is_form) {
+        params.extend(
+            String::from_utf8_lossy(body)
+                .split('&')
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+                    (form_decode(k), form_decode(v))
+                }),
+        );
+    }
+    params
+}
+
+fn form_decode(input: &str) -> String {
+    percent_decode_str(&input.replace('+', " "))
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+/// RFC 5849 signature base string: method, base URL and the sorted, encoded parameters
+fn signature_base(method: &str, url: &str, params: &[(String, String)]) -> String {
+    let mut encoded: Vec<(String, String)> = params
+        .iter()
+        .map(|(k, v)| (percent_encode(k), percent_encode(v)))
+        .collect();
+    encoded.sort();
+    let param_string = encoded
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    format!(
+        "{}&{}&{}",
+        method,
+        percent_encode(url),
+        percent_encode(&param_string)
+    )
+}
+
+/// OAuth 1.0a HMAC-SHA1 signature over `method`, `url` and every signed parameter
+pub fn oauth_signature(
+    method: &str,
+    url: &str,
+    params: &[(String, String)],
+    consumer_secret: &str,
+    token_secret: &str,
+) -> Result<String, TwitterError> {
+    let signing_key = format!(
+        "{}&{}",
+        percent_encode(consumer_secret),
+        percent_encode(token_secret)
+    );
+
+    let mut mac = HmacSha1::new_from_slice(signing_key.as_bytes())
+        .map_err(|e| TwitterError::Signing(e.to_string()))?;
+    mac.update(signature_base(method, url, params).as_bytes());
+    Ok(general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference_signer() -> OAuth1Signer {
+        OAuth1Signer::new(
+            "xvz1evFS4wEEPTGEFPHBog".to_string(),
+            "kAcSOqF21Fu85e7zjz7ZN2U4ZRhfV3WpwPAoE3Z7kBw".to_string(),
+            "370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb".to_string(),
+            "LswwdoUaIvS8ltyTt5jkRh4J50vUPVVHtR2YPi5kE".to_string(),
+        )
+        .with_clock(|| 1318622958)
+        .with_nonce(|| "kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg".to_string())
+    }
+
+    #[test]
+    fn test_signs_query_and_form_body_like_reference_example() {
+        // Worked example from X's "Creating a signature" guide
+        let request = Client::new()
+            .post("https://api.twitter.com/1.1/statuses/update.json")
+            .query(&[("include_entities", "true")])
+            .form(&[("status", "Hello Ladies + Gentlemen, a signed OAuth request!")])
+            .build()
+            .unwrap();
+
+        let header = reference_signer().header_for(&request).unwrap();
+
+        assert_eq!(
+            header,
+            "OAuth oauth_consumer_key=\"xvz1evFS4wEEPTGEFPHBog\", \
+             oauth_nonce=\"kYjzVBB8Y0ZFabxSWbWovY3uYSQ2pTgmZeNu2VS4cg\", \
+             oauth_signature=\"hCtSmYh%2BiHYCEqBWrE7C7hYmtUk%3D\", \
+             oauth_signature_method=\"HMAC-SHA1\", \
+             oauth_timestamp=\"1318622958\", \
+             oauth_token=\"370773112-GmHxMAgYyLbNEtIKZeRNFsMKPR9EyMZeS9weJAEb\", \
+             oauth_version=\"1.0\""
+        );
+    }
+
+    #[test]
+    fn test_base_string_matches_rfc_5849_example() {
+        // RFC 5849 section 3.4.1.1: repeated `a3`, empty values, a key-only field
+        let request = Client::new()
+            .post("http://example.com/request?b5=%3D%253D&a3=a&c%40=&a2=r%20b")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body("c2&a3=2+q")
+            .build()
+            .unwrap();
+
+        let mut params = request_params(&request);
+        params.extend([
+            ("oauth_consumer_key".to_string(), "9djdj82h48djs9d2".to_string()),
+            ("oauth_token".to_string(), "kkk9d7dh3k39sjv7".to_string()),
+            ("oauth_signature_method".to_string(), "HMAC-SHA1".to_string()),
+            ("oauth_timestamp".to_string(), "137131201".to_string()),
+            ("oauth_nonce".to_string(), "7d8f3e4a".to_string()),
+        ]);
+
+        assert_eq!(
+            signature_base("POST", "http://example.com/request", &params),
+            "POST&http%3A%2F%2Fexample.com%2Frequest&a2%3Dr%2520b%26a3%3D2%2520q\
+             %26a3%3Da%26b5%3D%253D%25253D%26c%2540%3D%26c2%3D%26oauth_consumer_\
+             key%3D9djdj82h48djs9d2%26oauth_nonce%3D7d8f3e4a%26oauth_signature_m\
+             ethod%3DHMAC-SHA1%26oauth_timestamp%3D137131201%26oauth_token%3Dkkk\
+             9d7dh3k39sjv7"
+        );
+    }
+
+    #[test]
+    fn test_json_body_is_not_signed() {
+        let request = Client::new()
+            .post("https://api.x.com/2/tweets")
+            .json(&serde_json::json!({ "text": "hello" }))
+            .build()
+            .unwrap();
+        assert!(request_params(&request).is_empty());
+
+        let mut request = request;
+        reference_signer().authorize(&mut request).unwrap();
+        assert!(request.headers()[AUTHORIZATION].to_str().unwrap().starts_with("OAuth "));
+    }
+
+    #[test]
+    fn test_sync_clock_shifts_timestamps_to_server_time() {
+        let signer = reference_signer();
+        let request = Client::new().get("https://api.x.com/2/users/me").build().unwrap();
+        let mut headers = HeaderMap::new();
+
+        // 1318622958 + 600 seconds
+        headers.insert(DATE, HeaderValue::from_static("Fri, 14 Oct 2011 20:19:18 GMT"));
+        assert!(signer.sync_clock(&headers));
+        assert!(signer.header_for(&request).unwrap().contains(r#"oauth_timestamp="1318623558""#));
+
+        // Within tolerance of the current correction
+        headers.insert(DATE, HeaderValue::from_static("Fri, 14 Oct 2011 20:19:20 GMT"));
+        assert!(!signer.sync_clock(&headers));
+        assert!(!signer.sync_clock(&HeaderMap::new()));
+    }
+}
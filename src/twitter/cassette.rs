@@ -0,0 +1,267 @@
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::debug;
+
+use super::{TwitterClient, TwitterError};
+
+/// Response headers worth keeping; everything else is dropped on record
+const KEPT_HEADERS: &[&str] = &[
+    "content-type",
+    "x-rate-limit-limit",
+    "x-rate-limit-remaining",
+    "x-rate-limit-reset",
+    "x-user-limit-24hour-limit",
+    "x-user-limit-24hour-remaining",
+    "x-user-limit-24hour-reset",
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// One recorded request/response pair
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<String>,
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl Interaction {
+    /// Rebuild the recorded response as if it had come off the wire
+    fn into_response(self) -> Result<Response, TwitterError> {
+        let mut builder = http::Response::builder().status(self.status);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .body(self.body)
+            .map_err(|e| TwitterError::Cassette(e.to_string()))?;
+        Ok(Response::from(response))
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+enum Mode {
+    Record,
+    /// Flags which recorded interactions have already been served
+    Replay(Vec<bool>),
+}
+
+/// Request/response pairs on disk, either being recorded from live traffic or
+/// served back in place of the network.
+///
+/// Recorded cassettes never contain the Authorization header, and every
+/// credential the client knows about is replaced with `[REDACTED]`.
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mutex<Mode>,
+    interactions: Mutex<Vec<Interaction>>,
+}
+
+impl Cassette {
+    /// Record every request to `path`, appending to any existing cassette
+    pub fn record(path: impl AsRef<Path>) -> Result<Self, TwitterError> {
+        let path = path.as_ref().to_path_buf();
+        let existing = if path.exists() {
+            load(&path)?.interactions
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path,
+            mode: Mutex::new(Mode::Record),
+            interactions: Mutex::new(existing),
+        })
+    }
+
+    /// Serve responses from the cassette at `path` instead of the network
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, TwitterError> {
+        let path = path.as_ref().to_path_buf();
+        let interactions = load(&path)?.interactions;
+        Ok(Self {
+            path,
+            mode: Mutex::new(Mode::Replay(vec![false; interactions.len()])),
+            interactions: Mutex::new(interactions),
+        })
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(*self.mode.lock().unwrap(), Mode::Replay(_))
+    }
+
+    /// The first not-yet-served interaction for `method` and `url`
+    pub fn next(&self, method: &str, url: &str) -> Result<Interaction, TwitterError> {
+        let mut mode = self.mode.lock().unwrap();
+        let Mode::Replay(served) = &mut *mode else {
+            return Err(TwitterError::Cassette(format!(
+                "{} is recording, not replaying",
+                self.path.display()
+            )));
+        };
+
+        let interactions = self.interactions.lock().unwrap();
+        let index = interactions
+            .iter()
+            .zip(served.iter())
+            .position(|(rec, done)| !done && rec.method == method && rec.url == url)
+            .ok_or_else(|| {
+                TwitterError::Cassette(format!(
+                    "no recorded response left for {} {} in {}",
+                    method,
+                    url,
+                    self.path.display()
+                ))
+            })?;
+        served[index] = true;
+        debug!("Replaying {} {} from {}", method, url, self.path.display());
+        Ok(interactions[index].clone())
+    }
+
+    /// Append an interaction, scrubbing `secrets` from it, and rewrite the file
+    pub fn record_interaction(
+        &self,
+        mut interaction: Interaction,
+        secrets: &[&str],
+    ) -> Result<(), TwitterError> {
+        interaction
+            .headers
+            .retain(|name, _| KEPT_HEADERS.contains(&name.as_str()));
+        interaction.url = redact(&interaction.url, secrets);
+        interaction.request_body = interaction.request_body.map(|b| redact(&b, secrets));
+        interaction.body = redact(&interaction.body, secrets);
+
+        let mut interactions = self.interactions.lock().unwrap();
+        interactions.push(interaction);
+        let file = CassetteFile {
+            interactions: interactions.clone(),
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| TwitterError::Cassette(e.to_string()))?;
+        std::fs::write(&self.path, json)
+            .map_err(|e| TwitterError::Cassette(format!("writing {}: {}", self.path.display(), e)))
+    }
+}
+
+impl TwitterClient {
+    /// Serve `request` from the cassette, or send it and record the exchange
+    pub(super) async fn send_via_cassette(
+        &self,
+        cassette: &Cassette,
+        request: Request,
+    ) -> Result<Response, TwitterError> {
+        let method = request.method().to_string();
+        let url = request.url().to_string();
+        if cassette.is_replay() {
+            return cassette.next(&method, &url)?.into_response();
+        }
+
+        let request_body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .map(|b| String::from_utf8_lossy(b).into_owned());
+        let response = self.client.execute(request).await?;
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                let value = value.to_str().ok()?;
+                Some((name.as_str().to_string(), value.to_string()))
+            })
+            .collect();
+        let interaction = Interaction {
+            method,
+            url,
+            request_body,
+            status: response.status().as_u16(),
+            headers,
+            body: response.text().await?,
+        };
+
+        let mut secrets = self.auth.secrets();
+        if let Some(app_auth) = &self.app_auth {
+            secrets.extend(app_auth.secrets());
+        }
+        let secrets: Vec<&str> = secrets.iter().map(String::as_str).collect();
+        cassette.record_interaction(interaction.clone(), &secrets)?;
+        interaction.into_response()
+    }
+}
+
+fn load(path: &Path) -> Result<CassetteFile, TwitterError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| TwitterError::Cassette(format!("reading {}: {}", path.display(), e)))?;
+    serde_json::from_str(&text)
+        .map_err(|e| TwitterError::Cassette(format!("parsing {}: {}", path.display(), e)))
+}
+
+fn redact(text: &str, secrets: &[&str]) -> String {
+    secrets
+        .iter()
+        .filter(|s| !s.is_empty())
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret, REDACTED)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interaction(url: &str, body: &str) -> Interaction {
+        Interaction {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            request_body: None,
+            status: 200,
+            headers: BTreeMap::from([
+                ("content-type".to_string(), "application/json".to_string()),
+                ("set-cookie".to_string(), "guest_id=abc".to_string()),
+            ]),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_record_redacts_and_replay_serves_in_order() {
+        let path = std::env::temp_dir().join(format!("cassette-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let recorder = Cassette::record(&path).unwrap();
+        let url = "https://api.x.com/2/users/1/mentions";
+        recorder
+            .record_interaction(
+                interaction(url, r#"{"token":"tok-secret","n":1}"#),
+                &["tok-secret"],
+            )
+            .unwrap();
+        recorder
+            .record_interaction(interaction(url, r#"{"n":2}"#), &["tok-secret"])
+            .unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("tok-secret"));
+        assert!(!saved.contains("set-cookie"));
+
+        let player = Cassette::replay(&path).unwrap();
+        assert!(player.is_replay());
+        assert!(player.next("GET", url).unwrap().body.contains(REDACTED));
+        assert_eq!(player.next("GET", url).unwrap().body, r#"{"n":2}"#);
+        assert!(matches!(
+            player.next("GET", url),
+            Err(TwitterError::Cassette(_))
+        ));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{MentionAuthor, MentionsIncludes, Priority, TwitterClient, TwitterError, MENTION_USER_FIELDS};
+
+// Rate-limit buckets for direct messages; X counts these apart from tweets
+const DM_EVENTS: &str = "GET /2/dm_events";
+const SEND_DM: &str = "POST /2/dm_conversations/with/:participant_id/messages";
+
+const DM_EVENT_FIELDS: &str = "id,text,event_type,created_at,sender_id,dm_conversation_id";
+
+/// One event in a DM conversation; only `MessageCreate` events are requested
+#[derive(Deserialize, Debug, Clone)]
+pub struct DmEvent {
+    pub id: String,
+    pub event_type: String,
+    #[serde(default)]
+    pub text: String,
+    pub sender_id: Option<String>,
+    pub dm_conversation_id: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Filled from the `sender_id` expansion
+    #[serde(skip)]
+    pub sender: Option<MentionAuthor>,
+}
+
+impl DmEvent {
+    pub fn is_message(&self) -> bool {
+        self.event_type == "MessageCreate"
+    }
+
+    pub fn sender_handle(&self) -> String {
+        match (&self.sender, &self.sender_id) {
+            (Some(sender), _) => format!("@{}", sender.username),
+            (None, Some(id)) => id.clone(),
+            (None, None) => "unknown sender".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct DmEventsResponse {
+    #[serde(default)]
+    pub data: Vec<DmEvent>,
+    #[serde(default)]
+    pub includes: MentionsIncludes,
+    pub meta: Option<DmEventsMeta>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct DmEventsMeta {
+    pub next_token: Option<String>,
+}
+
+impl DmEventsResponse {
+    /// Token for the next (older) page, if there is one
+    pub fn next_token(&self) -> Option<&str> {
+        self.meta.as_ref().and_then(|m| m.next_token.as_deref())
+    }
+
+    /// Append an older page fetched with this response's `next_token`
+    pub fn extend(&mut self, page: DmEventsResponse) {
+        self.data.extend(page.data);
+        self.includes.users.extend(page.includes.users);
+        self.meta = page.meta;
+    }
+
+    /// Attach each event's expanded sender from `includes.users`
+    pub(super) fn resolve_senders(&mut self) {
+        for event in &mut self.data {
+            event.sender = self
+                .includes
+                .users
+                .iter()
+                .find(|u| Some(&u.id) == event.sender_id.as_ref())
+                .cloned();
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NewMessage<'a> {
+    text: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SendDmResponse {
+    pub data: SentDm,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SentDm {
+    #[allow(dead_code)]
+    pub dm_conversation_id: String,
+    pub dm_event_id: String,
+}
+
+impl TwitterClient {
+    /// Messages across all of the user's DM conversations, newest first.
+    /// The endpoint takes no `since_id`, so callers page back until they
+    /// reach an event they've already handled.
+    pub async fn get_dm_events(
+        &self,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<DmEventsResponse, TwitterError> {
+        let url = self.api_url("/2/dm_events");
+
+        let max_results = max_results.clamp(1, 100).to_string();
+        let mut query_params = vec![
+            ("event_types", "MessageCreate"),
+            ("max_results", max_results.as_str()),
+            ("dm_event.fields", DM_EVENT_FIELDS),
+            ("expansions", "sender_id"),
+            ("user.fields", MENTION_USER_FIELDS),
+        ];
+        if let Some(token) = pagination_token {
+            query_params.push(("pagination_token", token));
+        }
+
+        let request = self.client.get(&url).query(&query_params);
+        let response = self.send(DM_EVENTS, Priority::Background, request).await?;
+
+        let mut events = response.json::<DmEventsResponse>().await?;
+        events.resolve_senders();
+        Ok(events)
+    }
+
+    /// Send `text` to `participant_id` in the one-to-one conversation with them
+    pub async fn send_dm(&self, participant_id: &str, text: &str) -> Result<SendDmResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/dm_conversations/with/{}/messages", participant_id));
+
+        let request = self.client.post(&url).json(&NewMessage { text });
+        let response = self.send(SEND_DM, Priority::Background, request).await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dm_events_resolve_senders() {
+        let mut events: DmEventsResponse = serde_json::from_str(
+            r#"{"data":[{"id":"900","event_type":"MessageCreate","text":"how do I install it?",
+                         "sender_id":"7","dm_conversation_id":"7-1000"},
+                        {"id":"899","event_type":"MessageCreate","text":"hi","sender_id":"8"}],
+                "includes":{"users":[{"id":"7","username":"alice"}]},
+                "meta":{"result_count":2,"next_token":"older"}}"#,
+        )
+        .unwrap();
+        events.resolve_senders();
+
+        assert!(events.data.iter().all(DmEvent::is_message));
+        assert_eq!(events.data[0].sender_handle(), "@alice");
+        assert_eq!(events.data[1].sender_handle(), "8");
+        assert_eq!(events.next_token(), Some("older"));
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Priority, TwitterClient, TwitterError};
+
+// Rate-limit buckets for likes, retweets, follows and deletes
+const LIKE: &str = "POST /2/users/:id/likes";
+const UNLIKE: &str = "DELETE /2/users/:id/likes/:tweet_id";
+const RETWEET: &str = "POST /2/users/:id/retweets";
+const UNRETWEET: &str = "DELETE /2/users/:id/retweets/:tweet_id";
+const DELETE_TWEET: &str = "DELETE /2/tweets/:id";
+const FOLLOW: &str = "POST /2/users/:id/following";
+
+#[derive(Serialize)]
+struct TweetIdRequest<'a> {
+    tweet_id: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LikeResponse {
+    pub data: LikeData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LikeData {
+    pub liked: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RetweetResponse {
+    pub data: RetweetData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RetweetData {
+    pub retweeted: bool,
+}
+
+#[derive(Serialize)]
+struct FollowRequest<'a> {
+    target_user_id: &'a str,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FollowResponse {
+    pub data: FollowData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FollowData {
+    pub following: bool,
+    /// Set instead of `following` when the target's account is protected
+    #[serde(default)]
+    pub pending_follow: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeleteResponse {
+    pub data: DeleteData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeleteData {
+    pub deleted: bool,
+}
+
+impl TwitterClient {
+    /// Like `tweet_id` as `user_id` (the authenticated user)
+    pub async fn like(&self, user_id: &str, tweet_id: &str) -> Result<LikeResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/users/{}/likes", user_id));
+
+        let request = self.client.post(&url).json(&TweetIdRequest { tweet_id });
+        let response = self.send(LIKE, Priority::Background, request).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Remove a like
+    pub async fn unlike(&self, user_id: &str, tweet_id: &str) -> Result<LikeResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/users/{}/likes/{}", user_id, tweet_id));
+
+        let request = self.client.delete(&url);
+        let response = self.send(UNLIKE, Priority::Background, request).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retweet `tweet_id` as `user_id` (the authenticated user)
+    pub async fn retweet(&self, user_id: &str, tweet_id: &str) -> Result<RetweetResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/users/{}/retweets", user_id));
+
+        let request = self.client.post(&url).json(&TweetIdRequest { tweet_id });
+        let response = self.send(RETWEET, Priority::Background, request).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Undo a retweet
+    pub async fn unretweet(&self, user_id: &str, tweet_id: &str) -> Result<RetweetResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/users/{}/retweets/{}", user_id, tweet_id));
+
+        let request = self.client.delete(&url);
+        let response = self.send(UNRETWEET, Priority::Background, request).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Follow `target_user_id` as `user_id` (the authenticated user)
+    pub async fn follow(&self, user_id: &str, target_user_id: &str) -> Result<FollowResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/users/{}/following", user_id));
+
+        let request = self.client.post(&url).json(&FollowRequest { target_user_id });
+        let response = self.send(FOLLOW, Priority::Background, request).await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Delete one of our own tweets
+    pub async fn delete_tweet(&self, tweet_id: &str) -> Result<DeleteResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/tweets/{}", tweet_id));
+
+        let request = self.client.delete(&url);
+        // Taking back a bad post shouldn't wait behind background work
+        let response = self.send(DELETE_TWEET, Priority::Scheduled, request).await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_responses_parse() {
+        let like: LikeResponse = serde_json::from_str(r#"{"data":{"liked":true}}"#).unwrap();
+        assert!(like.data.liked);
+        let retweet: RetweetResponse =
+            serde_json::from_str(r#"{"data":{"retweeted":false}}"#).unwrap();
+        assert!(!retweet.data.retweeted);
+        let delete: DeleteResponse = serde_json::from_str(r#"{"data":{"deleted":true}}"#).unwrap();
+        assert!(delete.data.deleted);
+        let follow: FollowResponse =
+            serde_json::from_str(r#"{"data":{"following":false,"pending_follow":true}}"#).unwrap();
+        assert!(!follow.data.following && follow.data.pending_follow);
+    }
+}
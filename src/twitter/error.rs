@@ -0,0 +1,242 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::{Response, StatusCode};
+use serde::Deserialize;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Problem details returned by the v2 API on errors
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ApiProblem {
+    pub title: Option<String>,
+    pub detail: Option<String>,
+    #[serde(rename = "type")]
+    pub problem_type: Option<String>,
+}
+
+impl ApiProblem {
+    /// Parse a v2 problem body, falling back to the raw text as the detail
+    fn parse(body: &str) -> Self {
+        serde_json::from_str::<ApiProblem>(body)
+            .ok()
+            .filter(|p| p.title.is_some() || p.detail.is_some())
+            .unwrap_or_else(|| ApiProblem {
+                detail: Some(body.to_string()),
+                ..Default::default()
+            })
+    }
+
+    fn describe(&self) -> String {
+        let text = match (&self.title, &self.detail) {
+            (Some(title), Some(detail)) => format!("{}: {}", title, detail),
+            (Some(title), None) => title.clone(),
+            (None, Some(detail)) => detail.clone(),
+            (None, None) => "no details".to_string(),
+        };
+        match self.problem_type.as_deref() {
+            Some(t) if t != "about:blank" => format!("{} [{}]", text, t),
+            _ => text,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TwitterError {
+    #[error("Twitter rate limit exceeded (resets at {reset_at:?}): {}", problem.describe())]
+    RateLimited {
+        reset_at: Option<DateTime<Utc>>,
+        problem: ApiProblem,
+    },
+
+    #[error("Twitter authentication failed: {}", problem.describe())]
+    Unauthorized { problem: ApiProblem },
+
+    #[error("Twitter rejected duplicate content: {}", problem.describe())]
+    DuplicateContent { problem: ApiProblem },
+
+    #[error("Twitter API forbidden: {}", problem.describe())]
+    Forbidden { problem: ApiProblem },
+
+    #[error("Twitter resource not found: {}", problem.describe())]
+    NotFound { problem: ApiProblem },
+
+    #[error("Twitter server error ({status}): {}", problem.describe())]
+    Server {
+        status: StatusCode,
+        problem: ApiProblem,
+    },
+
+    #[error("Twitter API error ({status}): {}", problem.describe())]
+    Api {
+        status: StatusCode,
+        problem: ApiProblem,
+    },
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("OAuth signing error: {0}")]
+    Signing(String),
+
+    #[error("OAuth 2.0 error: {0}")]
+    OAuth(String),
+
+    #[error("Twitter media processing failed: {0}")]
+    MediaProcessing(String),
+
+    #[error("Filtered stream error: {0}")]
+    Stream(String),
+
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+
+    #[error("Thread stopped after {} part(s): {source}", posted.len())]
+    ThreadIncomplete {
+        /// IDs of the parts that were posted before the failure
+        posted: Vec<String>,
+        source: Box<TwitterError>,
+    },
+}
+
+/// v1.1 error code for an `oauth_timestamp` too far from X's clock
+const TIMESTAMP_OUT_OF_BOUNDS: u64 = 135;
+
+/// Whether a 401 body says X rejected the OAuth timestamp, rather than the
+/// credentials. v1.1 reports code 135; v2 only says so in the detail.
+pub(super) fn timestamp_rejected(body: &str) -> bool {
+    let code_135 = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("errors")?.as_array().cloned())
+        .is_some_and(|errors| {
+            errors
+                .iter()
+                .any(|e| e.get("code").and_then(|c| c.as_u64()) == Some(TIMESTAMP_OUT_OF_BOUNDS))
+        });
+    code_135 || body.to_lowercase().contains("timestamp out of bounds")
+}
+
+impl TwitterError {
+    /// Build an error from a non-success response, consuming its body
+    pub async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let reset_at = rate_limit_reset(response.headers());
+        let body = response.text().await.unwrap_or_default();
+        Self::from_parts(status, reset_at, &body)
+    }
+
+    pub(super) fn from_parts(status: StatusCode, reset_at: Option<DateTime<Utc>>, body: &str) -> Self {
+        let problem = ApiProblem::parse(body);
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => TwitterError::RateLimited { reset_at, problem },
+            StatusCode::UNAUTHORIZED => TwitterError::Unauthorized { problem },
+            StatusCode::FORBIDDEN => {
+                let duplicate = problem
+                    .detail
+                    .as_deref()
+                    .map(|d| d.to_lowercase().contains("duplicate"))
+                    .unwrap_or(false);
+                if duplicate {
+                    TwitterError::DuplicateContent { problem }
+                } else {
+                    TwitterError::Forbidden { problem }
+                }
+            }
+            StatusCode::NOT_FOUND => TwitterError::NotFound { problem },
+            s if s.is_server_error() => TwitterError::Server { status, problem },
+            _ => TwitterError::Api { status, problem },
+        }
+    }
+
+    /// Whether trying the same request again later can succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TwitterError::RateLimited { .. } | TwitterError::Server { .. } => true,
+            TwitterError::Http(e) => e.is_timeout() || e.is_connect(),
+            TwitterError::ThreadIncomplete { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// When the exhausted rate-limit window resets, if known
+    pub fn rate_limit_reset(&self) -> Option<DateTime<Utc>> {
+        match self {
+            TwitterError::RateLimited { reset_at, .. } => *reset_at,
+            TwitterError::ThreadIncomplete { source, .. } => source.rate_limit_reset(),
+            _ => None,
+        }
+    }
+
+    /// How long to wait before retrying a rate-limited request
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.rate_limit_reset()
+            .map(|reset| (reset - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+    }
+}
+
+/// Read the reset time of whichever rate-limit window is exhausted
+pub(super) fn rate_limit_reset(headers: &HeaderMap) -> Option<DateTime<Utc>> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    // The 24-hour user cap on writes is reported separately from the 15-minute window
+    let key = if header("x-user-limit-24hour-remaining") == Some("0") {
+        "x-user-limit-24hour-reset"
+    } else {
+        "x-rate-limit-reset"
+    };
+
+    header(key)
+        .and_then(|v| v.parse::<i64>().ok())
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_content_detected() {
+        let body = r#"{"title":"Forbidden","detail":"You are not allowed to create a Tweet with duplicate content.","type":"about:blank","status":403}"#;
+        let err = TwitterError::from_parts(StatusCode::FORBIDDEN, None, body);
+        assert!(matches!(err, TwitterError::DuplicateContent { .. }));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_rate_limit_carries_reset() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rate-limit-reset", "1700000000".parse().unwrap());
+        let reset = rate_limit_reset(&headers);
+        let err = TwitterError::from_parts(StatusCode::TOO_MANY_REQUESTS, reset, "{}");
+        assert!(err.is_retryable());
+        assert_eq!(err.rate_limit_reset().unwrap().timestamp(), 1_700_000_000);
+    }
+
+    #[test]
+    fn test_non_json_body_kept_as_detail() {
+        let err = TwitterError::from_parts(StatusCode::BAD_GATEWAY, None, "upstream down");
+        assert!(err.is_retryable());
+        assert!(err.to_string().contains("upstream down"));
+    }
+
+    #[test]
+    fn test_unauthorized_not_retryable() {
+        let body = r#"{"title":"Unauthorized","type":"about:blank","status":401,"detail":"Unauthorized"}"#;
+        let err = TwitterError::from_parts(StatusCode::UNAUTHORIZED, None, body);
+        assert!(matches!(err, TwitterError::Unauthorized { .. }));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_timestamp_rejection_detected() {
+        assert!(timestamp_rejected(
+            r#"{"title":"Unauthorized","detail":"Timestamp out of bounds."}"#
+        ));
+        assert!(timestamp_rejected(
+            r#"{"errors":[{"code":135,"message":"Timestamp out of bounds."}]}"#
+        ));
+        assert!(!timestamp_rejected(
+            r#"{"title":"Unauthorized","type":"about:blank","status":401,"detail":"Unauthorized"}"#
+        ));
+        assert!(!timestamp_rejected(r#"{"errors":[{"code":32,"message":"Could not authenticate you."}]}"#));
+    }
+}
@@ -0,0 +1,302 @@
+use reqwest::StatusCode;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use super::dm::{DmEventsResponse, SendDmResponse, SentDm};
+use super::engagement::{FollowData, FollowResponse, LikeData, LikeResponse};
+use super::followers::FollowersResponse;
+use super::metrics::TweetsLookupResponse;
+use super::error::ApiProblem;
+use super::{AuthenticatedUser, MentionsResponse, Poll, SocialClient, TweetData, TweetResponse, TwitterError};
+
+/// A write the fake accepted
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakePost {
+    pub id: String,
+    pub text: String,
+    pub in_reply_to: Option<String>,
+    pub quote_of: Option<String>,
+    pub poll_options: Vec<String>,
+}
+
+/// In-memory `SocialClient` that records writes and serves queued mention pages
+#[derive(Default)]
+pub struct FakeClient {
+    posts: Mutex<Vec<FakePost>>,
+    mention_pages: Mutex<VecDeque<MentionsResponse>>,
+    search_pages: Mutex<VecDeque<MentionsResponse>>,
+    likes: Mutex<Vec<String>>,
+    dm_pages: Mutex<VecDeque<DmEventsResponse>>,
+    dms: Mutex<Vec<(String, String)>>,
+    follower_pages: Mutex<VecDeque<FollowersResponse>>,
+    follows: Mutex<Vec<String>>,
+    metrics_pages: Mutex<VecDeque<TweetsLookupResponse>>,
+    polls: Mutex<VecDeque<Poll>>,
+    failing_writes: Mutex<u32>,
+}
+
+impl FakeClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a mentions page, as v2 API JSON, for the next `get_mentions` call
+    pub fn push_mentions(&self, json: &str) {
+        let page: MentionsResponse = serde_json::from_str(json).expect("invalid mentions JSON");
+        self.mention_pages.lock().unwrap().push_back(page);
+    }
+
+    /// Queue a recent-search page, as v2 API JSON, for the next `search_recent` call
+    pub fn push_search(&self, json: &str) {
+        let page: MentionsResponse = serde_json::from_str(json).expect("invalid search JSON");
+        self.search_pages.lock().unwrap().push_back(page);
+    }
+
+    /// IDs of liked tweets, in order
+    pub fn likes(&self) -> Vec<String> {
+        self.likes.lock().unwrap().clone()
+    }
+
+    /// Queue a DM events page, as v2 API JSON, for the next `get_dm_events` call
+    pub fn push_dms(&self, json: &str) {
+        let page: DmEventsResponse = serde_json::from_str(json).expect("invalid DM events JSON");
+        self.dm_pages.lock().unwrap().push_back(page);
+    }
+
+    /// Sent DMs as (recipient ID, text), in order
+    pub fn dms(&self) -> Vec<(String, String)> {
+        self.dms.lock().unwrap().clone()
+    }
+
+    /// Queue a followers page, as v2 API JSON, for the next `get_followers` call
+    pub fn push_followers(&self, json: &str) {
+        let page: FollowersResponse = serde_json::from_str(json).expect("invalid followers JSON");
+        self.follower_pages.lock().unwrap().push_back(page);
+    }
+
+    /// IDs of followed accounts, in order
+    pub fn follows(&self) -> Vec<String> {
+        self.follows.lock().unwrap().clone()
+    }
+
+    /// Queue a tweet lookup, as v2 API JSON, for the next `get_tweet_metrics` call
+    pub fn push_metrics(&self, json: &str) {
+        let page: TweetsLookupResponse = serde_json::from_str(json).expect("invalid tweets JSON");
+        self.metrics_pages.lock().unwrap().push_back(page);
+    }
+
+    /// Queue a poll, as v2 API JSON, for the next `get_poll` call; without
+    /// one the tweet has no poll
+    pub fn push_poll(&self, json: &str) {
+        let poll: Poll = serde_json::from_str(json).expect("invalid poll JSON");
+        self.polls.lock().unwrap().push_back(poll);
+    }
+
+    /// Make the next `count` writes fail with a 503
+    pub fn fail_writes(&self, count: u32) {
+        *self.failing_writes.lock().unwrap() = count;
+    }
+
+    pub fn posts(&self) -> Vec<FakePost> {
+        self.posts.lock().unwrap().clone()
+    }
+
+    /// Fail this write if `fail_writes` asked for it
+    fn check_write(&self) -> Result<(), TwitterError> {
+        let mut failing = self.failing_writes.lock().unwrap();
+        if *failing == 0 {
+            return Ok(());
+        }
+        *failing -= 1;
+        Err(TwitterError::Server {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            problem: ApiProblem::default(),
+        })
+    }
+
+    fn write(
+        &self,
+        text: &str,
+        in_reply_to: Option<&str>,
+        poll_options: &[String],
+    ) -> Result<TweetResponse, TwitterError> {
+        self.check_write()?;
+        let mut posts = self.posts.lock().unwrap();
+        let id = (9000 + posts.len()).to_string();
+        posts.push(FakePost {
+            id: id.clone(),
+            text: text.to_string(),
+            in_reply_to: in_reply_to.map(str::to_string),
+            quote_of: None,
+            poll_options: poll_options.to_vec(),
+        });
+        Ok(TweetResponse {
+            data: TweetData {
+                id,
+                text: text.to_string(),
+            },
+        })
+    }
+}
+
+impl SocialClient for FakeClient {
+    async fn post_tweet(&self, text: &str, _media_ids: &[String]) -> Result<TweetResponse, TwitterError> {
+        self.write(text, None, &[])
+    }
+
+    async fn reply_to_tweet(
+        &self,
+        tweet_id: &str,
+        text: &str,
+        _media_ids: &[String],
+    ) -> Result<TweetResponse, TwitterError> {
+        self.write(text, Some(tweet_id), &[])
+    }
+
+    async fn post_thread(&self, parts: &[String]) -> Result<Vec<String>, TwitterError> {
+        self.resume_thread(parts, None, Vec::new()).await
+    }
+
+    async fn reply_with_thread(&self, tweet_id: &str, parts: &[String]) -> Result<Vec<String>, TwitterError> {
+        self.resume_thread(parts, Some(tweet_id), Vec::new()).await
+    }
+
+    async fn resume_thread(
+        &self,
+        parts: &[String],
+        in_reply_to: Option<&str>,
+        mut posted: Vec<String>,
+    ) -> Result<Vec<String>, TwitterError> {
+        for part in parts.iter().skip(posted.len()) {
+            let parent = posted.last().map(String::as_str).or(in_reply_to);
+            match self.write(part, parent, &[]) {
+                Ok(response) => posted.push(response.data.id),
+                Err(e) => {
+                    return Err(TwitterError::ThreadIncomplete {
+                        posted,
+                        source: Box::new(e),
+                    })
+                }
+            }
+        }
+        Ok(posted)
+    }
+
+    async fn post_poll(
+        &self,
+        text: &str,
+        options: &[String],
+        _duration_minutes: u32,
+    ) -> Result<TweetResponse, TwitterError> {
+        self.write(text, None, options)
+    }
+
+    async fn get_poll(&self, _tweet_id: &str) -> Result<Option<Poll>, TwitterError> {
+        Ok(self.polls.lock().unwrap().pop_front())
+    }
+
+    async fn upload_media(
+        &self,
+        _bytes: &[u8],
+        _media_type: &str,
+        _alt_text: Option<&str>,
+    ) -> Result<String, TwitterError> {
+        Ok("media-1".to_string())
+    }
+
+    async fn get_mentions(
+        &self,
+        _user_id: &str,
+        _since_id: Option<&str>,
+        _pagination_token: Option<&str>,
+        _max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError> {
+        let page = self.mention_pages.lock().unwrap().pop_front();
+        let mut page = page.unwrap_or_else(|| serde_json::from_str(r#"{"meta":{"result_count":0}}"#).unwrap());
+        page.resolve_authors();
+        Ok(page)
+    }
+
+    async fn search_recent(
+        &self,
+        _query: &str,
+        _since_id: Option<&str>,
+        _max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError> {
+        let page = self.search_pages.lock().unwrap().pop_front();
+        let mut page = page.unwrap_or_else(|| serde_json::from_str(r#"{"meta":{"result_count":0}}"#).unwrap());
+        page.resolve_authors();
+        Ok(page)
+    }
+
+    async fn like(&self, _user_id: &str, tweet_id: &str) -> Result<LikeResponse, TwitterError> {
+        self.check_write()?;
+        self.likes.lock().unwrap().push(tweet_id.to_string());
+        Ok(LikeResponse {
+            data: LikeData { liked: true },
+        })
+    }
+
+    async fn quote_tweet(&self, tweet_id: &str, text: &str) -> Result<TweetResponse, TwitterError> {
+        let response = self.write(text, None, &[])?;
+        if let Some(post) = self.posts.lock().unwrap().last_mut() {
+            post.quote_of = Some(tweet_id.to_string());
+        }
+        Ok(response)
+    }
+
+    /// Always the `test_config` account
+    async fn verify_credentials(&self) -> Result<AuthenticatedUser, TwitterError> {
+        Ok(AuthenticatedUser {
+            id: "1000".to_string(),
+            username: "test".to_string(),
+            protected: false,
+        })
+    }
+
+    async fn get_dm_events(
+        &self,
+        _pagination_token: Option<&str>,
+        _max_results: u32,
+    ) -> Result<DmEventsResponse, TwitterError> {
+        let mut page = self.dm_pages.lock().unwrap().pop_front().unwrap_or_default();
+        page.resolve_senders();
+        Ok(page)
+    }
+
+    async fn send_dm(&self, participant_id: &str, text: &str) -> Result<SendDmResponse, TwitterError> {
+        self.check_write()?;
+        let mut dms = self.dms.lock().unwrap();
+        dms.push((participant_id.to_string(), text.to_string()));
+        Ok(SendDmResponse {
+            data: SentDm {
+                dm_conversation_id: format!("{}-1000", participant_id),
+                dm_event_id: (8000 + dms.len()).to_string(),
+            },
+        })
+    }
+
+    async fn get_followers(
+        &self,
+        _user_id: &str,
+        _pagination_token: Option<&str>,
+        _max_results: u32,
+    ) -> Result<FollowersResponse, TwitterError> {
+        Ok(self.follower_pages.lock().unwrap().pop_front().unwrap_or_default())
+    }
+
+    async fn follow(&self, _user_id: &str, target_user_id: &str) -> Result<FollowResponse, TwitterError> {
+        self.check_write()?;
+        self.follows.lock().unwrap().push(target_user_id.to_string());
+        Ok(FollowResponse {
+            data: FollowData {
+                following: true,
+                pending_follow: false,
+            },
+        })
+    }
+
+    async fn get_tweet_metrics(&self, _ids: &[String]) -> Result<TweetsLookupResponse, TwitterError> {
+        Ok(self.metrics_pages.lock().unwrap().pop_front().unwrap_or_default())
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{Priority, TwitterClient, TwitterError, UserMetrics, USER_FOLLOWERS};
+
+const FOLLOWER_USER_FIELDS: &str = "created_at,description,public_metrics";
+
+/// An account following the bot, with what the follow-back rules look at
+#[derive(Deserialize, Debug, Clone)]
+pub struct Follower {
+    pub id: String,
+    pub username: String,
+    /// The profile bio
+    #[serde(default)]
+    pub description: String,
+    /// When the account was created
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub public_metrics: UserMetrics,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct FollowersResponse {
+    #[serde(default)]
+    pub data: Vec<Follower>,
+    pub meta: Option<FollowersMeta>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct FollowersMeta {
+    pub next_token: Option<String>,
+}
+
+impl FollowersResponse {
+    /// Token for the next page of (earlier) followers, if there is one
+    pub fn next_token(&self) -> Option<&str> {
+        self.meta.as_ref().and_then(|m| m.next_token.as_deref())
+    }
+}
+
+impl TwitterClient {
+    /// Accounts following `user_id`, most recent follows first
+    pub async fn get_followers(
+        &self,
+        user_id: &str,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<FollowersResponse, TwitterError> {
+        let url = self.api_url(&format!("/2/users/{}/followers", user_id));
+
+        let max_results = max_results.clamp(1, 1000).to_string();
+        let mut query_params = vec![
+            ("max_results", max_results.as_str()),
+            ("user.fields", FOLLOWER_USER_FIELDS),
+        ];
+        if let Some(token) = pagination_token {
+            query_params.push(("pagination_token", token));
+        }
+
+        let request = self.client.get(&url).query(&query_params);
+        let response = self
+            .send(USER_FOLLOWERS, Priority::Background, request)
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_followers_parse() {
+        let followers: FollowersResponse = serde_json::from_str(
+            r#"{"data":[{"id":"7","username":"alice","description":"Rust + agents",
+                         "created_at":"2020-01-01T00:00:00.000Z",
+                         "public_metrics":{"followers_count":42,"following_count":10}},
+                        {"id":"8","username":"bob"}],
+                "meta":{"result_count":2,"next_token":"older"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(followers.data[0].public_metrics.followers_count, 42);
+        assert!(followers.data[0].created_at.is_some());
+        assert_eq!(followers.data[1].description, "");
+        assert_eq!(followers.next_token(), Some("older"));
+    }
+}
@@ -0,0 +1,201 @@
+use base64::{engine::general_purpose, Engine as _};
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::{Priority, TwitterClient, TwitterError};
+
+const UPLOAD_PATH: &str = "/1.1/media/upload.json";
+const METADATA_PATH: &str = "/1.1/media/metadata/create.json";
+
+// Rate-limit buckets for the v1.1 upload endpoints
+const MEDIA_UPLOAD: &str = "POST /1.1/media/upload";
+const MEDIA_STATUS: &str = "GET /1.1/media/upload";
+const MEDIA_METADATA: &str = "POST /1.1/media/metadata/create";
+
+/// Bytes per APPEND segment; stays well under the 5 MB limit once base64-encoded
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// STATUS checks before giving up on media X is still processing
+const MAX_STATUS_CHECKS: u32 = 30;
+
+/// Response to INIT, FINALIZE and STATUS
+#[derive(Deserialize, Debug)]
+pub struct MediaUpload {
+    pub media_id_string: String,
+    pub processing_info: Option<ProcessingInfo>,
+}
+
+/// Async processing progress reported after FINALIZE
+#[derive(Deserialize, Debug)]
+pub struct ProcessingInfo {
+    pub state: String,
+    pub check_after_secs: Option<u64>,
+    pub error: Option<ProcessingError>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProcessingError {
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MetadataRequest<'a> {
+    media_id: &'a str,
+    alt_text: AltText<'a>,
+}
+
+#[derive(Serialize)]
+struct AltText<'a> {
+    text: &'a str,
+}
+
+/// MIME type for an image file, by extension
+pub fn image_media_type(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+impl TwitterClient {
+    /// Upload an image through the chunked INIT/APPEND/FINALIZE flow, waiting
+    /// on STATUS if X processes it asynchronously. Returns the media ID to
+    /// attach to a post.
+    pub async fn upload_media(
+        &self,
+        bytes: &[u8],
+        media_type: &str,
+        alt_text: Option<&str>,
+    ) -> Result<String, TwitterError> {
+        let init: MediaUpload = self
+            .media_command(BTreeMap::from([
+                ("command".to_string(), "INIT".to_string()),
+                ("total_bytes".to_string(), bytes.len().to_string()),
+                ("media_type".to_string(), media_type.to_string()),
+                ("media_category".to_string(), "tweet_image".to_string()),
+            ]))
+            .await?
+            .json()
+            .await?;
+        let media_id = init.media_id_string;
+        debug!("Media {} initialized ({} bytes)", media_id, bytes.len());
+
+        for (index, chunk) in bytes.chunks(CHUNK_SIZE).enumerate() {
+            self.media_command(BTreeMap::from([
+                ("command".to_string(), "APPEND".to_string()),
+                ("media_id".to_string(), media_id.clone()),
+                ("segment_index".to_string(), index.to_string()),
+                ("media_data".to_string(), general_purpose::STANDARD.encode(chunk)),
+            ]))
+            .await?;
+        }
+
+        let mut upload: MediaUpload = self
+            .media_command(BTreeMap::from([
+                ("command".to_string(), "FINALIZE".to_string()),
+                ("media_id".to_string(), media_id.clone()),
+            ]))
+            .await?
+            .json()
+            .await?;
+
+        let mut checks = 0;
+        while let Some(info) = upload.processing_info {
+            match info.state.as_str() {
+                "succeeded" => break,
+                "failed" => {
+                    let message = info
+                        .error
+                        .and_then(|e| e.message)
+                        .unwrap_or_else(|| "no details".to_string());
+                    return Err(TwitterError::MediaProcessing(message));
+                }
+                state if checks >= MAX_STATUS_CHECKS => {
+                    return Err(TwitterError::MediaProcessing(format!(
+                        "still {} after {} status checks",
+                        state, checks
+                    )));
+                }
+                state => {
+                    checks += 1;
+                    let wait = Duration::from_secs(info.check_after_secs.unwrap_or(1));
+                    debug!("Media {} is {}, checking again in {:?}", media_id, state, wait);
+                    tokio::time::sleep(wait).await;
+                    upload = self.media_status(&media_id).await?;
+                }
+            }
+        }
+
+        if let Some(alt) = alt_text {
+            self.set_alt_text(&media_id, alt).await?;
+        }
+
+        info!("Media {} uploaded", media_id);
+        Ok(media_id)
+    }
+
+    /// POST one upload command as a form body; the signer covers its fields
+    async fn media_command(&self, params: BTreeMap<String, String>) -> Result<Response, TwitterError> {
+        let request = self.client.post(self.upload_url(UPLOAD_PATH)).form(&params);
+        self.send(MEDIA_UPLOAD, Priority::Scheduled, request).await
+    }
+
+    async fn media_status(&self, media_id: &str) -> Result<MediaUpload, TwitterError> {
+        let query_params = BTreeMap::from([
+            ("command".to_string(), "STATUS".to_string()),
+            ("media_id".to_string(), media_id.to_string()),
+        ]);
+        let request = self
+            .client
+            .get(self.upload_url(UPLOAD_PATH))
+            .query(&query_params);
+        let response = self.send(MEDIA_STATUS, Priority::Scheduled, request).await?;
+
+        Ok(response.json().await?)
+    }
+
+    async fn set_alt_text(&self, media_id: &str, alt_text: &str) -> Result<(), TwitterError> {
+        let request = self
+            .client
+            .post(self.upload_url(METADATA_PATH))
+            .json(&MetadataRequest {
+                media_id,
+                alt_text: AltText { text: alt_text },
+            });
+        self.send(MEDIA_METADATA, Priority::Scheduled, request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_media_type() {
+        assert_eq!(image_media_type(Path::new("swarm.PNG")), Some("image/png"));
+        assert_eq!(image_media_type(Path::new("a/b/market.jpeg")), Some("image/jpeg"));
+        assert_eq!(image_media_type(Path::new("notes.txt")), None);
+        assert_eq!(image_media_type(Path::new("no_extension")), None);
+    }
+
+    #[test]
+    fn test_processing_info_parsed() {
+        let upload: MediaUpload = serde_json::from_str(
+            r#"{"media_id":710511363345354753,"media_id_string":"710511363345354753",
+                "processing_info":{"state":"pending","check_after_secs":5}}"#,
+        )
+        .unwrap();
+        let info = upload.processing_info.unwrap();
+        assert_eq!(info.state, "pending");
+        assert_eq!(info.check_after_secs, Some(5));
+    }
+}
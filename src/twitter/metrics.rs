@@ -0,0 +1,78 @@
+use serde::Deserialize;
+
+use super::{Priority, TwitterClient, TwitterError, TWEETS_LOOKUP};
+
+/// Most IDs one `/2/tweets` lookup accepts
+pub const MAX_LOOKUP_IDS: usize = 100;
+
+/// A tweet's public engagement counts
+#[derive(Deserialize, Debug, Clone)]
+pub struct TweetMetrics {
+    pub id: String,
+    #[serde(default)]
+    pub public_metrics: PublicMetrics,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct PublicMetrics {
+    #[serde(default)]
+    pub like_count: u64,
+    #[serde(default)]
+    pub retweet_count: u64,
+    #[serde(default)]
+    pub reply_count: u64,
+    #[serde(default)]
+    pub quote_count: u64,
+    /// Missing for older tweets and some access levels
+    pub impression_count: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct TweetsLookupResponse {
+    /// Deleted or hidden tweets are reported under `errors` instead
+    #[serde(default)]
+    pub data: Vec<TweetMetrics>,
+}
+
+impl TwitterClient {
+    /// Public metrics for up to `MAX_LOOKUP_IDS` tweets in one read
+    pub async fn get_tweet_metrics(
+        &self,
+        ids: &[String],
+    ) -> Result<TweetsLookupResponse, TwitterError> {
+        let url = self.api_url("/2/tweets");
+
+        let ids = ids[..ids.len().min(MAX_LOOKUP_IDS)].join(",");
+        let request = self
+            .client
+            .get(&url)
+            .query(&[("ids", ids.as_str()), ("tweet.fields", "public_metrics")]);
+        let response = self
+            .send(TWEETS_LOOKUP, Priority::Background, request)
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_skips_deleted_tweets() {
+        let lookup: TweetsLookupResponse = serde_json::from_str(
+            r#"{"data":[{"id":"10","text":"hi","public_metrics":{"retweet_count":2,"reply_count":1,
+                          "like_count":9,"quote_count":0,"bookmark_count":3,"impression_count":410}},
+                        {"id":"11","text":"old","public_metrics":{"like_count":1}}],
+                "errors":[{"value":"12","detail":"Could not find tweet with ids: [12].",
+                           "title":"Not Found Error","type":"https://api.twitter.com/2/problems/resource-not-found"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(lookup.data.len(), 2);
+        assert_eq!(lookup.data[0].public_metrics.like_count, 9);
+        assert_eq!(lookup.data[0].public_metrics.impression_count, Some(410));
+        assert_eq!(lookup.data[1].public_metrics.impression_count, None);
+    }
+}
@@ -0,0 +1,682 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use percent_encoding::percent_decode_str;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::auth::oauth_signature;
+use super::oauth2::OAuth2Tokens;
+use super::{
+    OAuth2App, OAuth2Session, TwitterClient, CREATE_TWEET, USERS_ME, USER_BY_USERNAME, USER_MENTIONS,
+};
+
+pub const CONSUMER_KEY: &str = "mock-consumer-key";
+pub const CONSUMER_SECRET: &str = "mock-consumer-secret";
+pub const ACCESS_TOKEN: &str = "mock-access-token";
+pub const ACCESS_TOKEN_SECRET: &str = "mock-access-token-secret";
+/// App-only bearer token handed out for the mock consumer key and secret
+pub const APP_BEARER: &str = "mock-app-bearer";
+
+/// Requests allowed per endpoint in each 15-minute window
+const WINDOW_LIMIT: u32 = 50;
+const WINDOW_SECS: i64 = 15 * 60;
+/// How far `oauth_timestamp` may stray from the server clock, as on X
+const TIMESTAMP_WINDOW_SECS: i64 = 5 * 60;
+
+struct Request {
+    method: String,
+    path: String,
+    query: BTreeMap<String, String>,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Reply {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Value,
+}
+
+impl Reply {
+    fn json(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    fn problem(status: u16, title: &str, detail: &str) -> Self {
+        Self::json(
+            status,
+            json!({ "title": title, "detail": detail, "type": "about:blank", "status": status }),
+        )
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    /// username -> user ID
+    users: HashMap<String, String>,
+    /// Username the mock credentials belong to, answered by `/2/users/me`
+    me: Option<String>,
+    /// Mentions of any user, newest first
+    mentions: Vec<Value>,
+    /// Bodies of accepted `POST /2/tweets` calls, with the assigned `id` added
+    posted: Vec<Value>,
+    next_id: u64,
+    remaining: HashMap<&'static str, u32>,
+    window_reset: i64,
+    /// Bumped on every OAuth 2.0 refresh; only the latest token pair is valid
+    token_generation: u32,
+    /// Seconds the server clock runs ahead of the local one
+    clock_offset: i64,
+    /// Requests served so far
+    requests: usize,
+}
+
+impl MockState {
+    fn now(&self) -> chrono::DateTime<Utc> {
+        Utc::now() + chrono::Duration::seconds(self.clock_offset)
+    }
+
+    fn bearer_token(&self) -> String {
+        format!("mock-bearer-{}", self.token_generation)
+    }
+
+    fn refresh_token(&self) -> String {
+        format!("mock-refresh-{}", self.token_generation)
+    }
+}
+
+/// Local HTTP server emulating the slice of the X v2 API that `TwitterClient` uses.
+///
+/// Every request must carry a valid OAuth 1.0a signature for the mock
+/// credentials or a current OAuth 2.0 bearer token (app-only tokens are
+/// refused on writes and `/2/users/me`) and an `oauth_timestamp` close to the server clock. Every
+/// response carries a `Date` header and `x-rate-limit-*` headers; an endpoint
+/// answers 429 once its window is used up.
+pub struct MockServer {
+    url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockState {
+            next_id: 1_900_000_000_000_000_000,
+            window_reset: Utc::now().timestamp() + WINDOW_SECS,
+            token_generation: 1,
+            ..Default::default()
+        }));
+
+        let server_url = url.clone();
+        let server_state = Arc::clone(&state);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let url = server_url.clone();
+                let state = Arc::clone(&server_state);
+                tokio::spawn(async move {
+                    // A dropped connection only fails that one request
+                    let _ = serve(stream, &url, &state).await;
+                });
+            }
+        });
+
+        Self { url, state }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A client signed with the mock credentials and pointed at this server
+    pub fn client(&self) -> TwitterClient {
+        TwitterClient::with_credentials(
+            CONSUMER_KEY.to_string(),
+            CONSUMER_SECRET.to_string(),
+            ACCESS_TOKEN.to_string(),
+            ACCESS_TOKEN_SECRET.to_string(),
+        )
+        .with_base_url(&self.url)
+    }
+
+    /// An OAuth 2.0 session holding `access_token` and the server's current
+    /// refresh token, persisting refreshed tokens to `token_path`
+    pub fn oauth2_session(&self, token_path: &std::path::Path, access_token: &str) -> OAuth2Session {
+        let app = OAuth2App::new(
+            "mock-client".to_string(),
+            None,
+            "http://127.0.0.1:8765/callback".to_string(),
+        )
+        .with_token_url(&format!("{}/2/oauth2/token", self.url));
+        let tokens = OAuth2Tokens {
+            access_token: access_token.to_string(),
+            refresh_token: Some(self.state.lock().unwrap().refresh_token()),
+            expires_at: Utc::now() + chrono::Duration::hours(2),
+            scope: None,
+        };
+        OAuth2Session::new(app, token_path, tokens)
+    }
+
+    /// The bearer token the server currently accepts
+    pub fn bearer_token(&self) -> String {
+        self.state.lock().unwrap().bearer_token()
+    }
+
+    pub fn add_user(&self, username: &str, id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.users.insert(username.to_lowercase(), id.to_string());
+    }
+
+    /// Make the mock credentials belong to `username`, adding the user too
+    pub fn set_authenticated_user(&self, username: &str, id: &str) {
+        self.add_user(username, id);
+        self.state.lock().unwrap().me = Some(username.to_string());
+    }
+
+    pub fn add_mention(&self, id: u64, author_id: &str, text: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.mentions.push(json!({
+            "id": id.to_string(),
+            "text": text,
+            "author_id": author_id,
+            "created_at": Utc::now().to_rfc3339(),
+        }));
+        state.mentions.sort_by_key(|m| std::cmp::Reverse(mention_id(m)));
+    }
+
+    /// Use up `endpoint`'s window so its next call gets a 429
+    pub fn exhaust(&self, endpoint: &'static str) {
+        self.state.lock().unwrap().remaining.insert(endpoint, 0);
+    }
+
+    /// Run the server clock `seconds` ahead of (or behind) the local one
+    pub fn set_clock_offset(&self, seconds: i64) {
+        self.state.lock().unwrap().clock_offset = seconds;
+    }
+
+    pub fn posted(&self) -> Vec<Value> {
+        self.state.lock().unwrap().posted.clone()
+    }
+
+    /// How many requests the server has answered
+    pub fn requests(&self) -> usize {
+        self.state.lock().unwrap().requests
+    }
+}
+
+fn mention_id(mention: &Value) -> u64 {
+    mention["id"].as_str().and_then(|id| id.parse().ok()).unwrap_or(0)
+}
+
+/// Decode a query, form or header value; `+` is a space in the first two
+fn decode(value: &str) -> String {
+    percent_decode_str(&value.replace('+', " ")).decode_utf8_lossy().into_owned()
+}
+
+async fn serve(stream: TcpStream, base_url: &str, state: &Mutex<MockState>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let (path, query_string) = target.split_once('?').unwrap_or((target, ""));
+    let query = query_string
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (decode(k), decode(v)))
+        .collect();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+
+    let request = Request {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+        body,
+    };
+    let (reply, date) = {
+        let mut state = state.lock().unwrap();
+        state.requests += 1;
+        (handle(&request, base_url, &mut state), state.now())
+    };
+
+    let body = reply.body.to_string();
+    let mut response = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nDate: {}\r\nConnection: close\r\n",
+        reply.status,
+        body.len(),
+        date.format("%a, %d %b %Y %H:%M:%S GMT")
+    );
+    for (name, value) in &reply.headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str("\r\n");
+    response.push_str(&body);
+
+    let mut stream = reader.into_inner();
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn handle(request: &Request, base_url: &str, state: &mut MockState) -> Reply {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    let endpoint = match (request.method.as_str(), segments.as_slice()) {
+        ("POST", ["2", "tweets"]) => CREATE_TWEET,
+        ("GET", ["2", "users", "me"]) => USERS_ME,
+        ("GET", ["2", "users", "by", "username", _]) => USER_BY_USERNAME,
+        ("GET", ["2", "users", _, "mentions"]) => USER_MENTIONS,
+        ("POST", ["2", "oauth2", "token"]) => return oauth2_token(request, state),
+        ("POST", ["oauth2", "token"]) => return app_token(request),
+        _ => return Reply::problem(404, "Not Found Error", "Unknown endpoint"),
+    };
+
+    let bearer = request
+        .headers
+        .get("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "));
+    let authorized = match bearer {
+        Some(APP_BEARER) if endpoint == CREATE_TWEET || endpoint == USERS_ME => {
+            return Reply::problem(
+                403,
+                "Unsupported Authentication",
+                "Authenticating with OAuth 2.0 Application-Only is forbidden for this endpoint.",
+            );
+        }
+        Some(token) => token == APP_BEARER || token == state.bearer_token(),
+        None => signature_valid(request, base_url),
+    };
+    if !authorized {
+        return Reply::problem(401, "Unauthorized", "Unauthorized");
+    }
+    if bearer.is_none() && !timestamp_fresh(request, state.now().timestamp()) {
+        return Reply::problem(401, "Unauthorized", "Timestamp out of bounds.");
+    }
+
+    // Rate-limit window shared by every endpoint's bucket
+    let now = Utc::now().timestamp();
+    if now >= state.window_reset {
+        state.window_reset = now + WINDOW_SECS;
+        state.remaining.clear();
+    }
+    let remaining = state.remaining.entry(endpoint).or_insert(WINDOW_LIMIT);
+    let limit_headers = |remaining: u32| {
+        vec![
+            ("x-rate-limit-limit", WINDOW_LIMIT.to_string()),
+            ("x-rate-limit-remaining", remaining.to_string()),
+            ("x-rate-limit-reset", state.window_reset.to_string()),
+        ]
+    };
+    if *remaining == 0 {
+        let mut reply = Reply::problem(429, "Too Many Requests", "Too Many Requests");
+        reply.headers = limit_headers(0);
+        return reply;
+    }
+    *remaining -= 1;
+    let headers = limit_headers(*remaining);
+
+    let mut reply = match endpoint {
+        CREATE_TWEET => create_tweet(request, state),
+        USERS_ME => me(state),
+        USER_BY_USERNAME => user_by_username(segments[4], state),
+        _ => mentions(request, state),
+    };
+    reply.headers = headers;
+    reply
+}
+
+/// Recompute the OAuth 1.0a signature from the header and query, as X would
+fn signature_valid(request: &Request, base_url: &str) -> bool {
+    let Some(fields) = request
+        .headers
+        .get("authorization")
+        .and_then(|h| h.strip_prefix("OAuth "))
+    else {
+        return false;
+    };
+
+    let mut params = BTreeMap::new();
+    let mut signature = None;
+    for field in fields.split(", ") {
+        let Some((key, value)) = field.split_once('=') else {
+            return false;
+        };
+        let value = decode(value.trim_matches('"'));
+        if key == "oauth_signature" {
+            signature = Some(value);
+        } else {
+            params.insert(key.to_string(), value);
+        }
+    }
+    if params.get("oauth_consumer_key").map(String::as_str) != Some(CONSUMER_KEY)
+        || params.get("oauth_token").map(String::as_str) != Some(ACCESS_TOKEN)
+    {
+        return false;
+    }
+    let mut params: Vec<(String, String)> = params.into_iter().collect();
+    params.extend(request.query.clone());
+
+    let url = format!("{}{}", base_url, request.path);
+    let expected = oauth_signature(&request.method, &url, &params, CONSUMER_SECRET, ACCESS_TOKEN_SECRET);
+    matches!((expected, signature), (Ok(e), Some(s)) if e == s)
+}
+
+fn timestamp_fresh(request: &Request, now: i64) -> bool {
+    request
+        .headers
+        .get("authorization")
+        .and_then(|h| h.split(", ").find_map(|f| f.strip_prefix("oauth_timestamp=")))
+        .and_then(|t| t.trim_matches('"').parse::<i64>().ok())
+        .map(|t| (t - now).abs() <= TIMESTAMP_WINDOW_SECS)
+        .unwrap_or(false)
+}
+
+fn form_fields(request: &Request) -> HashMap<String, String> {
+    String::from_utf8_lossy(&request.body)
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (decode(k), decode(v)))
+        .collect()
+}
+
+/// Client-credentials grant for the mock consumer key and secret
+fn app_token(request: &Request) -> Reply {
+    let expected = format!(
+        "Basic {}",
+        general_purpose::STANDARD.encode(format!("{}:{}", CONSUMER_KEY, CONSUMER_SECRET))
+    );
+    let form = form_fields(request);
+    if request.headers.get("authorization") != Some(&expected)
+        || form.get("grant_type").map(String::as_str) != Some("client_credentials")
+    {
+        return Reply::problem(403, "Forbidden", "Unable to verify your credentials");
+    }
+    Reply::json(200, json!({ "token_type": "bearer", "access_token": APP_BEARER }))
+}
+
+/// Refresh-token grant: rotate to the next token pair
+fn oauth2_token(request: &Request, state: &mut MockState) -> Reply {
+    let form = form_fields(request);
+    let valid = form.get("grant_type").map(String::as_str) == Some("refresh_token")
+        && form.get("refresh_token") == Some(&state.refresh_token());
+    if !valid {
+        return Reply::json(
+            400,
+            json!({ "error": "invalid_request", "error_description": "Value passed for the token was invalid." }),
+        );
+    }
+
+    state.token_generation += 1;
+    Reply::json(
+        200,
+        json!({
+            "token_type": "bearer",
+            "expires_in": 7200,
+            "access_token": state.bearer_token(),
+            "scope": "tweet.read tweet.write users.read offline.access",
+            "refresh_token": state.refresh_token(),
+        }),
+    )
+}
+
+fn create_tweet(request: &Request, state: &mut MockState) -> Reply {
+    let Ok(mut body) = serde_json::from_slice::<Value>(&request.body) else {
+        return Reply::problem(400, "Invalid Request", "Body is not JSON");
+    };
+    let Some(text) = body["text"].as_str().map(str::to_string) else {
+        return Reply::problem(400, "Invalid Request", "Missing text");
+    };
+    if state.posted.iter().any(|p| p["text"] == text.as_str()) {
+        return Reply::problem(
+            403,
+            "Forbidden",
+            "You are not allowed to create a Tweet with duplicate content.",
+        );
+    }
+
+    state.next_id += 1;
+    let id = state.next_id.to_string();
+    body["id"] = json!(id);
+    state.posted.push(body);
+    Reply::json(201, json!({ "data": { "id": id, "text": text } }))
+}
+
+fn me(state: &MockState) -> Reply {
+    match &state.me {
+        Some(username) => Reply::json(
+            200,
+            json!({ "data": {
+                "id": state.users[&username.to_lowercase()],
+                "name": username,
+                "username": username,
+                "protected": false,
+            } }),
+        ),
+        None => Reply::problem(401, "Unauthorized", "Unauthorized"),
+    }
+}
+
+fn user_by_username(username: &str, state: &MockState) -> Reply {
+    match state.users.get(&username.to_lowercase()) {
+        Some(id) => Reply::json(
+            200,
+            json!({ "data": { "id": id, "name": username, "username": username } }),
+        ),
+        None => Reply::problem(
+            404,
+            "Not Found Error",
+            &format!("Could not find user with username: [{}].", username),
+        ),
+    }
+}
+
+fn mentions(request: &Request, state: &MockState) -> Reply {
+    let since_id: u64 = request
+        .query
+        .get("since_id")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let max_results: usize = request
+        .query
+        .get("max_results")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10);
+    // Pagination tokens are plain offsets into the filtered list
+    let offset: usize = request
+        .query
+        .get("pagination_token")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let newer: Vec<&Value> = state
+        .mentions
+        .iter()
+        .filter(|m| mention_id(m) > since_id)
+        .collect();
+    let page: Vec<Value> = newer
+        .iter()
+        .skip(offset)
+        .take(max_results)
+        .map(|m| (*m).clone())
+        .collect();
+    if page.is_empty() {
+        return Reply::json(200, json!({ "meta": { "result_count": 0 } }));
+    }
+
+    let mut meta = json!({
+        "newest_id": page[0]["id"],
+        "oldest_id": page[page.len() - 1]["id"],
+        "result_count": page.len(),
+    });
+    if offset + page.len() < newer.len() {
+        meta["next_token"] = json!((offset + page.len()).to_string());
+    }
+    Reply::json(200, json!({ "data": page, "meta": meta }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitter::TwitterError;
+
+    #[tokio::test]
+    async fn test_accepts_signed_post_and_records_it() {
+        let server = MockServer::start().await;
+        let response = server.client().post_tweet("hello agents", &[]).await.unwrap();
+
+        let posted = server.posted();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0]["text"], "hello agents");
+        assert_eq!(posted[0]["id"], response.data.id.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_signature() {
+        let server = MockServer::start().await;
+        let client = TwitterClient::with_credentials(
+            CONSUMER_KEY.to_string(),
+            "wrong-secret".to_string(),
+            ACCESS_TOKEN.to_string(),
+            ACCESS_TOKEN_SECRET.to_string(),
+        )
+        .with_base_url(server.url());
+
+        let err = client.get_user_id("agentropic").await.unwrap_err();
+        assert!(matches!(err, TwitterError::Unauthorized { .. }));
+        assert!(server.posted().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_and_rate_limit_errors() {
+        let server = MockServer::start().await;
+        let client = server.client();
+        client.post_tweet("same text", &[]).await.unwrap();
+        let err = client.post_tweet("same text", &[]).await.unwrap_err();
+        assert!(matches!(err, TwitterError::DuplicateContent { .. }));
+
+        server.exhaust(USER_MENTIONS);
+        let err = client.get_mentions("1000", None, None, 10).await.unwrap_err();
+        assert!(matches!(err, TwitterError::RateLimited { .. }));
+        assert!(err.rate_limit_reset().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_mentions_paginate_and_respect_since_id() {
+        let server = MockServer::start().await;
+        for id in 1..=7 {
+            server.add_mention(id, "7", &format!("@agentropic question {}", id));
+        }
+        let client = server.client();
+
+        let mut first = client.get_mentions("1000", Some("1"), None, 5).await.unwrap();
+        assert_eq!(first.data.len(), 5);
+        assert_eq!(first.data[0].id, "7");
+        let token = first.next_token().unwrap().to_string();
+
+        let second = client.get_mentions("1000", Some("1"), Some(&token), 5).await.unwrap();
+        first.extend(second);
+        assert_eq!(first.data.len(), 6);
+        assert_eq!(first.next_token(), None);
+        assert_eq!(first.meta.unwrap().oldest_id.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_oauth2_refreshes_and_retries_after_401() {
+        let server = MockServer::start().await;
+        server.add_user("agentropic", "1000");
+        let token_path =
+            std::env::temp_dir().join(format!("oauth2-tokens-{}.json", std::process::id()));
+        let session = server.oauth2_session(&token_path, "expired-bearer");
+        let client = TwitterClient::with_authenticator(Box::new(session)).with_base_url(server.url());
+
+        assert_eq!(client.get_user_id("agentropic").await.unwrap(), "1000");
+
+        let saved = OAuth2Tokens::load(&token_path).unwrap();
+        assert_eq!(saved.access_token, server.bearer_token());
+        assert_eq!(saved.refresh_token.as_deref(), Some("mock-refresh-2"));
+        std::fs::remove_file(&token_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_app_bearer_used_for_reads_only() {
+        let server = MockServer::start().await;
+        server.add_user("agentropic", "1000");
+        let bearer = server
+            .client()
+            .fetch_app_bearer(CONSUMER_KEY, CONSUMER_SECRET)
+            .await
+            .unwrap();
+
+        // User-context credentials are wrong, so only app-only reads can succeed
+        let client = TwitterClient::with_credentials(
+            CONSUMER_KEY.to_string(),
+            "wrong-secret".to_string(),
+            ACCESS_TOKEN.to_string(),
+            ACCESS_TOKEN_SECRET.to_string(),
+        )
+        .with_base_url(server.url())
+        .with_app_auth(Box::new(bearer));
+
+        assert_eq!(client.get_user_id("agentropic").await.unwrap(), "1000");
+        let err = client.post_tweet("hello agents", &[]).await.unwrap_err();
+        assert!(matches!(err, TwitterError::Unauthorized { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_resigns_with_server_time_after_timestamp_rejection() {
+        let server = MockServer::start().await;
+        server.set_clock_offset(20 * 60);
+        let client = server.client();
+
+        // First attempt is rejected, the retry uses the corrected timestamp
+        client.post_tweet("hello from a slow clock", &[]).await.unwrap();
+        client.post_tweet("and again", &[]).await.unwrap();
+        assert_eq!(server.posted().len(), 2);
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_bad_credentials_are_not_resigned_when_the_clock_moves() {
+        let server = MockServer::start().await;
+        server.set_clock_offset(20 * 60);
+        let client = TwitterClient::with_credentials(
+            CONSUMER_KEY.to_string(),
+            "wrong-secret".to_string(),
+            ACCESS_TOKEN.to_string(),
+            ACCESS_TOKEN_SECRET.to_string(),
+        )
+        .with_base_url(server.url());
+
+        // The skew estimate changes, but the 401 isn't about the timestamp
+        let err = client.post_tweet("hello agents", &[]).await.unwrap_err();
+        assert!(matches!(err, TwitterError::Unauthorized { .. }));
+        assert_eq!(server.requests(), 1);
+    }
+}
@@ -0,0 +1,827 @@
+mod auth;
+mod cassette;
+mod dm;
+mod engagement;
+mod error;
+#[cfg(test)]
+mod fake;
+mod followers;
+mod media;
+mod metrics;
+#[cfg(test)]
+mod mock;
+mod oauth2;
+mod rate_limit;
+mod social;
+mod stream;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub use auth::{Authenticator, OAuth1Signer};
+pub use cassette::Cassette;
+pub use dm::{DmEvent, DmEventsResponse};
+pub use error::TwitterError;
+pub use followers::Follower;
+#[cfg(test)]
+pub use fake::FakeClient;
+pub use media::image_media_type;
+pub use metrics::MAX_LOOKUP_IDS;
+#[cfg(test)]
+pub use mock::MockServer;
+pub use oauth2::{AppBearer, OAuth2App, OAuth2Session, MEDIA_SCOPE};
+pub use rate_limit::{Priority, RateLimiter};
+pub use social::SocialClient;
+
+// Rate-limit buckets, one per endpoint as X accounts for them
+const CREATE_TWEET: &str = "POST /2/tweets";
+const USER_MENTIONS: &str = "GET /2/users/:id/mentions";
+const USER_BY_USERNAME: &str = "GET /2/users/by/username/:username";
+const TWEET_LOOKUP: &str = "GET /2/tweets/:id";
+const USERS_ME: &str = "GET /2/users/me";
+const RECENT_SEARCH: &str = "GET /2/tweets/search/recent";
+const USER_FOLLOWERS: &str = "GET /2/users/:id/followers";
+const TWEETS_LOOKUP: &str = "GET /2/tweets";
+const FILTERED_STREAM: &str = "GET /2/tweets/search/stream";
+const STREAM_RULES: &str = "GET /2/tweets/search/stream/rules";
+const UPDATE_STREAM_RULES: &str = "POST /2/tweets/search/stream/rules";
+
+/// Fields requested for mentions, whether polled or streamed
+const MENTION_TWEET_FIELDS: &str =
+    "author_id,text,created_at,conversation_id,lang,referenced_tweets,in_reply_to_user_id";
+const MENTION_USER_FIELDS: &str = "username,verified,public_metrics";
+
+/// Whose credentials an endpoint is called with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthPolicy {
+    /// Writes and anything acting as the bot account
+    UserContext,
+    /// Public reads, which get their own rate-limit pool under app-only auth
+    AppOnly,
+}
+
+fn auth_policy(endpoint: &str) -> AuthPolicy {
+    match endpoint {
+        USER_BY_USERNAME | USER_MENTIONS | TWEET_LOOKUP | TWEETS_LOOKUP | RECENT_SEARCH
+        | USER_FOLLOWERS => AuthPolicy::AppOnly,
+        // The filtered stream only accepts app-only auth
+        FILTERED_STREAM | STREAM_RULES | UPDATE_STREAM_RULES => AuthPolicy::AppOnly,
+        _ => AuthPolicy::UserContext,
+    }
+}
+
+/// Default hosts; `with_base_url` swaps both for a stand-in server
+const API_BASE_URL: &str = "https://api.x.com";
+const UPLOAD_BASE_URL: &str = "https://upload.twitter.com";
+
+pub struct TwitterClient {
+    client: Client,
+    api_base_url: String,
+    upload_base_url: String,
+    auth: Box<dyn Authenticator>,
+    /// App-only bearer token for the endpoints `auth_policy` allows it on
+    app_auth: Option<Box<dyn Authenticator>>,
+    rate_limiter: Arc<RateLimiter>,
+    cassette: Option<Cassette>,
+}
+
+#[derive(Serialize)]
+struct TweetRequest {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply: Option<ReplyTo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    media: Option<MediaAttachment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    poll: Option<PollRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quote_tweet_id: Option<String>,
+}
+
+impl TweetRequest {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            reply: None,
+            media: None,
+            poll: None,
+            quote_tweet_id: None,
+        }
+    }
+
+    fn in_reply_to(mut self, tweet_id: Option<&str>) -> Self {
+        self.reply = tweet_id.map(|id| ReplyTo {
+            in_reply_to_tweet_id: id.to_string(),
+        });
+        self
+    }
+
+    fn with_media(mut self, media_ids: &[String]) -> Self {
+        self.media = (!media_ids.is_empty()).then(|| MediaAttachment {
+            media_ids: media_ids.to_vec(),
+        });
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct ReplyTo {
+    in_reply_to_tweet_id: String,
+}
+
+#[derive(Serialize)]
+struct MediaAttachment {
+    media_ids: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PollRequest {
+    options: Vec<String>,
+    duration_minutes: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct TweetLookupResponse {
+    #[serde(default)]
+    includes: TweetIncludes,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct TweetIncludes {
+    #[serde(default)]
+    polls: Vec<Poll>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Poll {
+    #[allow(dead_code)]
+    pub id: String,
+    pub options: Vec<PollOption>,
+    pub voting_status: Option<String>,
+    pub end_datetime: Option<DateTime<Utc>>,
+}
+
+impl Poll {
+    pub fn is_closed(&self) -> bool {
+        self.voting_status.as_deref() == Some("closed")
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PollOption {
+    #[allow(dead_code)]
+    pub position: u32,
+    pub label: String,
+    #[serde(default)]
+    pub votes: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TweetResponse {
+    pub data: TweetData,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TweetData {
+    pub id: String,
+    #[allow(dead_code)]
+    pub text: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MentionsResponse {
+    #[serde(default)]
+    pub data: Vec<MentionData>,
+    #[serde(default)]
+    pub includes: MentionsIncludes,
+    pub meta: Option<MentionsMeta>,
+}
+
+impl MentionsResponse {
+    /// Token for the next (older) page, if there is one
+    pub fn next_token(&self) -> Option<&str> {
+        self.meta.as_ref().and_then(|m| m.next_token.as_deref())
+    }
+
+    /// Attach each mention's expanded author from `includes.users`
+    fn resolve_authors(&mut self) {
+        for mention in &mut self.data {
+            mention.author = self
+                .includes
+                .users
+                .iter()
+                .find(|u| u.id == mention.author_id)
+                .cloned();
+        }
+    }
+
+    /// Append an older page fetched with this response's `next_token`
+    pub fn extend(&mut self, page: MentionsResponse) {
+        self.data.extend(page.data);
+        self.includes.users.extend(page.includes.users);
+        match (self.meta.as_mut(), page.meta) {
+            (Some(meta), Some(older)) => {
+                meta.result_count += older.result_count;
+                meta.oldest_id = older.oldest_id;
+                meta.next_token = older.next_token;
+            }
+            (None, older) => self.meta = older,
+            (Some(_), None) => {}
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MentionData {
+    pub id: String,
+    pub text: String,
+    pub author_id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub conversation_id: Option<String>,
+    pub lang: Option<String>,
+    #[serde(default)]
+    pub referenced_tweets: Vec<ReferencedTweet>,
+    #[allow(dead_code)]
+    pub in_reply_to_user_id: Option<String>,
+    /// Filled from the `author_id` expansion
+    #[serde(skip)]
+    pub author: Option<MentionAuthor>,
+}
+
+impl MentionData {
+    pub fn is_retweet(&self) -> bool {
+        self.referenced_tweets
+            .iter()
+            .any(|r| r.kind == ReferenceKind::Retweeted)
+    }
+
+    /// Verified flag and follower count, for logging
+    pub fn author_context(&self) -> String {
+        match &self.author {
+            Some(a) => format!(
+                "{}{} followers",
+                if a.verified { "verified, " } else { "" },
+                a.public_metrics.followers_count
+            ),
+            None => "author not expanded".to_string(),
+        }
+    }
+
+    /// `@username` of the author if expanded, otherwise the raw author ID
+    pub fn author_handle(&self) -> String {
+        match &self.author {
+            Some(author) => format!("@{}", author.username),
+            None => self.author_id.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReferencedTweet {
+    #[serde(rename = "type")]
+    pub kind: ReferenceKind,
+    #[allow(dead_code)]
+    pub id: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    RepliedTo,
+    Quoted,
+    Retweeted,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct MentionsIncludes {
+    #[serde(default)]
+    pub users: Vec<MentionAuthor>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MentionAuthor {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub verified: bool,
+    #[serde(default)]
+    pub public_metrics: UserMetrics,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct UserMetrics {
+    #[serde(default)]
+    pub followers_count: u64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MentionsMeta {
+    pub newest_id: Option<String>,
+    pub oldest_id: Option<String>,
+    #[serde(default)]
+    pub result_count: u64,
+    pub next_token: Option<String>,
+}
+
+/// The account the user-context credentials belong to
+#[derive(Deserialize, Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub protected: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct MeResponse {
+    data: AuthenticatedUser,
+}
+
+#[cfg(test)]
+#[derive(Deserialize, Debug)]
+struct UserLookupResponse {
+    data: UserData,
+}
+
+#[cfg(test)]
+#[derive(Deserialize, Debug)]
+struct UserData {
+    id: String,
+}
+
+/// RFC 3986 percent-encoding for OAuth 1.0a
+fn percent_encode(input: &str) -> String {
+    let mut encoded = String::new();
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        }
+    }
+    encoded
+}
+
+impl TwitterClient {
+    pub fn new() -> Result<Self> {
+        let consumer_key = env::var("TWITTER_CONSUMER_KEY")
+            .map_err(|_| anyhow::anyhow!("TWITTER_CONSUMER_KEY not set"))?;
+        let consumer_secret = env::var("TWITTER_CONSUMER_SECRET")
+            .map_err(|_| anyhow::anyhow!("TWITTER_CONSUMER_SECRET not set"))?;
+        let access_token = env::var("TWITTER_ACCESS_TOKEN")
+            .map_err(|_| anyhow::anyhow!("TWITTER_ACCESS_TOKEN not set"))?;
+        let access_token_secret = env::var("TWITTER_ACCESS_TOKEN_SECRET")
+            .map_err(|_| anyhow::anyhow!("TWITTER_ACCESS_TOKEN_SECRET not set"))?;
+
+        Ok(Self::with_credentials(
+            consumer_key,
+            consumer_secret,
+            access_token,
+            access_token_secret,
+        ))
+    }
+
+    /// Build a client from explicit OAuth 1.0a credentials instead of env vars
+    pub fn with_credentials(
+        consumer_key: String,
+        consumer_secret: String,
+        access_token: String,
+        access_token_secret: String,
+    ) -> Self {
+        Self::with_authenticator(Box::new(OAuth1Signer::new(
+            consumer_key,
+            consumer_secret,
+            access_token,
+            access_token_secret,
+        )))
+    }
+
+    /// Build a client that authorizes requests with any `Authenticator`
+    pub fn with_authenticator(auth: Box<dyn Authenticator>) -> Self {
+        Self {
+            client: Client::new(),
+            api_base_url: API_BASE_URL.to_string(),
+            upload_base_url: UPLOAD_BASE_URL.to_string(),
+            auth,
+            app_auth: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
+            cassette: None,
+        }
+    }
+
+    /// Make public reads with an app-only bearer token instead of user context
+    pub fn with_app_auth(mut self, app_auth: Box<dyn Authenticator>) -> Self {
+        self.app_auth = Some(app_auth);
+        self
+    }
+
+    /// Share a rate limiter with other clients or jobs
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Send every request, uploads included, to `base_url` instead of X
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        self.api_base_url = base_url.to_string();
+        self.upload_base_url = base_url.to_string();
+        self
+    }
+
+    /// Record traffic to, or replay it from, a cassette file
+    pub fn with_cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Full URL for an API path such as `/2/tweets`
+    fn api_url(&self, path: &str) -> String {
+        format!("{}{}", self.api_base_url, path)
+    }
+
+    /// Full URL for a media upload path such as `/1.1/media/upload.json`
+    fn upload_url(&self, path: &str) -> String {
+        format!("{}{}", self.upload_base_url, path)
+    }
+
+    /// Send a request through the rate limiter and turn failures into typed errors
+    async fn send(
+        &self,
+        endpoint: &'static str,
+        priority: Priority,
+        request: RequestBuilder,
+    ) -> Result<Response, TwitterError> {
+        self.rate_limiter.acquire(endpoint, priority).await?;
+
+        let auth = self.authenticator_for(endpoint);
+        // Renew a bearer token that's about to expire before signing with it
+        auth.refresh(&self.client, false).await?;
+        let mut request = request.build()?;
+        auth.authorize(&mut request)?;
+        let retry = request.try_clone();
+
+        let mut response = self.execute(request).await?;
+        let clock_corrected = auth.sync_clock(response.headers());
+        if response.status() == StatusCode::UNAUTHORIZED {
+            if let Some(mut retry) = retry {
+                if clock_corrected {
+                    // Only re-sign when X says it was the timestamp; a bad
+                    // secret on a skewed machine shouldn't cost a second write
+                    self.rate_limiter.record(endpoint, response.headers());
+                    let status = response.status();
+                    let reset_at = error::rate_limit_reset(response.headers());
+                    let body = response.text().await.unwrap_or_default();
+                    if !error::timestamp_rejected(&body) {
+                        return Err(TwitterError::from_parts(status, reset_at, &body));
+                    }
+                    warn!("Local clock is off from X's, re-signing {}", endpoint);
+                    auth.authorize(&mut retry)?;
+                    response = self.execute(retry).await?;
+                } else if auth.refresh(&self.client, true).await? {
+                    info!("Credentials refreshed after a 401, retrying {}", endpoint);
+                    auth.authorize(&mut retry)?;
+                    response = self.execute(retry).await?;
+                }
+            }
+        }
+        self.rate_limiter.record(endpoint, response.headers());
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let error = TwitterError::from_response(response).await;
+        if let Some(reset_at) = error.rate_limit_reset() {
+            self.rate_limiter.exhaust(endpoint, reset_at);
+        }
+        Err(error)
+    }
+
+    async fn execute(&self, request: Request) -> Result<Response, TwitterError> {
+        match &self.cassette {
+            Some(cassette) => self.send_via_cassette(cassette, request).await,
+            None => Ok(self.client.execute(request).await?),
+        }
+    }
+
+    /// Post a new tweet, attaching any media uploaded with `upload_media`
+    pub async fn post_tweet(
+        &self,
+        text: &str,
+        media_ids: &[String],
+    ) -> Result<TweetResponse, TwitterError> {
+        let request = TweetRequest::new(text).with_media(media_ids);
+        self.create_tweet(request, Priority::Scheduled).await
+    }
+
+    /// Reply to a specific tweet, attaching any media uploaded with `upload_media`
+    pub async fn reply_to_tweet(
+        &self,
+        tweet_id: &str,
+        text: &str,
+        media_ids: &[String],
+    ) -> Result<TweetResponse, TwitterError> {
+        let request = TweetRequest::new(text)
+            .in_reply_to(Some(tweet_id))
+            .with_media(media_ids);
+        self.create_tweet(request, Priority::Background).await
+    }
+
+    /// Quote `tweet_id` with our own commentary
+    pub async fn quote_tweet(
+        &self,
+        tweet_id: &str,
+        text: &str,
+    ) -> Result<TweetResponse, TwitterError> {
+        let mut request = TweetRequest::new(text);
+        request.quote_tweet_id = Some(tweet_id.to_string());
+        self.create_tweet(request, Priority::Background).await
+    }
+
+    /// Post a poll with 2-4 options, open for `duration_minutes` (5 to 10080)
+    pub async fn post_poll(
+        &self,
+        text: &str,
+        options: &[String],
+        duration_minutes: u32,
+    ) -> Result<TweetResponse, TwitterError> {
+        let mut request = TweetRequest::new(text);
+        request.poll = Some(PollRequest {
+            options: options.to_vec(),
+            duration_minutes,
+        });
+        self.create_tweet(request, Priority::Scheduled).await
+    }
+
+    /// Fetch the poll attached to `tweet_id`, with vote counts
+    pub async fn get_poll(&self, tweet_id: &str) -> Result<Option<Poll>, TwitterError> {
+        let base_url = self.api_url(&format!("/2/tweets/{}", tweet_id));
+
+        let mut query_params = BTreeMap::new();
+        query_params.insert("expansions".to_string(), "attachments.poll_ids".to_string());
+        query_params.insert(
+            "poll.fields".to_string(),
+            "options,voting_status,end_datetime".to_string(),
+        );
+
+        let request = self.client.get(&base_url).query(&query_params);
+        let response = self.send(TWEET_LOOKUP, Priority::Background, request).await?;
+
+        let lookup: TweetLookupResponse = response.json().await?;
+        Ok(lookup.includes.polls.into_iter().next())
+    }
+
+    /// Post `parts` as a thread, each part replying to the one before it.
+    /// Returns the created tweet IDs in order.
+    pub async fn post_thread(&self, parts: &[String]) -> Result<Vec<String>, TwitterError> {
+        self.resume_thread(parts, None, Vec::new()).await
+    }
+
+    /// Reply to `tweet_id` with a thread
+    pub async fn reply_with_thread(
+        &self,
+        tweet_id: &str,
+        parts: &[String],
+    ) -> Result<Vec<String>, TwitterError> {
+        self.resume_thread(parts, Some(tweet_id), Vec::new()).await
+    }
+
+    /// Continue a thread that stopped part-way. `posted` holds the IDs of the
+    /// parts already created, as returned in `TwitterError::ThreadIncomplete`.
+    pub async fn resume_thread(
+        &self,
+        parts: &[String],
+        in_reply_to: Option<&str>,
+        mut posted: Vec<String>,
+    ) -> Result<Vec<String>, TwitterError> {
+        let priority = match in_reply_to {
+            Some(_) => Priority::Background,
+            None => Priority::Scheduled,
+        };
+
+        for part in parts.iter().skip(posted.len()) {
+            let parent = posted.last().map(String::as_str).or(in_reply_to);
+            let request = TweetRequest::new(part).in_reply_to(parent);
+            match self.create_tweet(request, priority).await {
+                Ok(response) => posted.push(response.data.id),
+                Err(e) => {
+                    return Err(TwitterError::ThreadIncomplete {
+                        posted,
+                        source: Box::new(e),
+                    })
+                }
+            }
+        }
+        Ok(posted)
+    }
+
+    async fn create_tweet(
+        &self,
+        tweet_request: TweetRequest,
+        priority: Priority,
+    ) -> Result<TweetResponse, TwitterError> {
+        let url = self.api_url("/2/tweets");
+
+        let request = self.client.post(&url).json(&tweet_request);
+        let response = self.send(CREATE_TWEET, priority, request).await?;
+
+        let tweet_response = response.json::<TweetResponse>().await?;
+        Ok(tweet_response)
+    }
+
+    /// Get one page of recent mentions for a user, newest first.
+    /// `max_results` is clamped to the 5..=100 range the API accepts.
+    pub async fn get_mentions(
+        &self,
+        user_id: &str,
+        since_id: Option<&str>,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError> {
+        let base_url = self.api_url(&format!("/2/users/{}/mentions", user_id));
+
+        let mut query_params = BTreeMap::new();
+        query_params.insert(
+            "max_results".to_string(),
+            max_results.clamp(5, 100).to_string(),
+        );
+        query_params.insert("tweet.fields".to_string(), MENTION_TWEET_FIELDS.to_string());
+        query_params.insert("expansions".to_string(), "author_id".to_string());
+        query_params.insert("user.fields".to_string(), MENTION_USER_FIELDS.to_string());
+
+        if let Some(sid) = since_id {
+            query_params.insert("since_id".to_string(), sid.to_string());
+        }
+        if let Some(token) = pagination_token {
+            query_params.insert("pagination_token".to_string(), token.to_string());
+        }
+
+        let request = self.client.get(&base_url).query(&query_params);
+        let response = self.send(USER_MENTIONS, Priority::Background, request).await?;
+
+        let mut mentions = response.json::<MentionsResponse>().await?;
+        mentions.resolve_authors();
+        Ok(mentions)
+    }
+
+    /// Search the last 7 days of tweets, newest first, in the same shape as
+    /// mentions. `max_results` is clamped to the 10..=100 range the API accepts.
+    pub async fn search_recent(
+        &self,
+        query: &str,
+        since_id: Option<&str>,
+        max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError> {
+        let url = self.api_url("/2/tweets/search/recent");
+
+        let max_results = max_results.clamp(10, 100).to_string();
+        let mut query_params = vec![
+            ("query", query),
+            ("max_results", max_results.as_str()),
+            ("tweet.fields", MENTION_TWEET_FIELDS),
+            ("expansions", "author_id"),
+            ("user.fields", MENTION_USER_FIELDS),
+        ];
+        if let Some(since_id) = since_id {
+            query_params.push(("since_id", since_id));
+        }
+
+        let request = self.client.get(&url).query(&query_params);
+        let response = self.send(RECENT_SEARCH, Priority::Background, request).await?;
+
+        let mut results = response.json::<MentionsResponse>().await?;
+        results.resolve_authors();
+        Ok(results)
+    }
+
+    /// Check the user-context credentials and return the account they belong to
+    pub async fn verify_credentials(&self) -> Result<AuthenticatedUser, TwitterError> {
+        let url = self.api_url("/2/users/me");
+
+        let request = self.client.get(&url).query(&[("user.fields", "protected")]);
+        let response = self.send(USERS_ME, Priority::Scheduled, request).await?;
+
+        let me: MeResponse = response.json().await?;
+        Ok(me.data)
+    }
+
+    /// Look up user ID from username
+    #[cfg(test)]
+    pub async fn get_user_id(&self, username: &str) -> Result<String, TwitterError> {
+        let base_url = self.api_url(&format!("/2/users/by/username/{}", username));
+
+        let request = self.client.get(&base_url);
+        let response = self.send(USER_BY_USERNAME, Priority::Scheduled, request).await?;
+
+        let user: UserLookupResponse = response.json().await?;
+        Ok(user.data.id)
+    }
+
+    /// The app-only bearer token for endpoints that allow it, otherwise user context
+    fn authenticator_for(&self, endpoint: &str) -> &dyn Authenticator {
+        match (&self.app_auth, auth_policy(endpoint)) {
+            (Some(app), AuthPolicy::AppOnly) => app.as_ref(),
+            _ => self.auth.as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extend_merges_pages() {
+        let mut first: MentionsResponse = serde_json::from_str(
+            r#"{"data":[{"id":"30","text":"a","author_id":"1"},{"id":"29","text":"b","author_id":"2"}],
+                "meta":{"newest_id":"30","oldest_id":"29","result_count":2,"next_token":"abc"}}"#,
+        )
+        .unwrap();
+        let second: MentionsResponse = serde_json::from_str(
+            r#"{"data":[{"id":"28","text":"c","author_id":"3"}],
+                "meta":{"newest_id":"28","oldest_id":"28","result_count":1}}"#,
+        )
+        .unwrap();
+        assert_eq!(first.next_token(), Some("abc"));
+
+        first.extend(second);
+        let meta = first.meta.as_ref().unwrap();
+        assert_eq!(first.data.len(), 3);
+        assert_eq!(meta.newest_id.as_deref(), Some("30"));
+        assert_eq!(meta.oldest_id.as_deref(), Some("28"));
+        assert_eq!(meta.result_count, 3);
+        assert_eq!(first.next_token(), None);
+    }
+
+    #[test]
+    fn test_authors_resolved_from_includes() {
+        let mut page: MentionsResponse = serde_json::from_str(
+            r#"{"data":[{"id":"30","text":"hi","author_id":"7","lang":"en",
+                         "created_at":"2025-04-01T12:00:00.000Z","conversation_id":"29",
+                         "in_reply_to_user_id":"1","referenced_tweets":[{"type":"replied_to","id":"29"}]},
+                        {"id":"31","text":"rt","author_id":"8","referenced_tweets":[{"type":"retweeted","id":"5"}]}],
+                "includes":{"users":[{"id":"7","username":"alice","name":"Alice","verified":true,
+                                      "public_metrics":{"followers_count":1200,"following_count":3,"tweet_count":40,"listed_count":1}}]},
+                "meta":{"result_count":2}}"#,
+        )
+        .unwrap();
+        page.resolve_authors();
+
+        let first = &page.data[0];
+        let author = first.author.as_ref().unwrap();
+        assert_eq!(first.author_handle(), "@alice");
+        assert!(author.verified);
+        assert_eq!(author.public_metrics.followers_count, 1200);
+        assert_eq!(first.created_at.unwrap().timestamp(), 1_743_508_800);
+        assert!(!first.is_retweet());
+        assert_eq!(first.in_reply_to_user_id.as_deref(), Some("1"));
+        assert_eq!(first.referenced_tweets[0].id, "29");
+
+        let second = &page.data[1];
+        assert!(second.author.is_none());
+        assert_eq!(second.author_handle(), "8");
+        assert!(second.is_retweet());
+    }
+
+    #[test]
+    fn test_empty_page_has_no_data() {
+        let page: MentionsResponse =
+            serde_json::from_str(r#"{"meta":{"result_count":0}}"#).unwrap();
+        assert!(page.data.is_empty());
+        assert_eq!(page.next_token(), None);
+    }
+
+    #[tokio::test]
+    async fn test_replayed_mentions_with_suspended_author() {
+        let cassette = Cassette::replay(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/cassettes/mentions_suspended_author.json"
+        ))
+        .unwrap();
+        let client = TwitterClient::with_credentials(
+            "key".to_string(),
+            "secret".to_string(),
+            "token".to_string(),
+            "token-secret".to_string(),
+        )
+        .with_cassette(cassette);
+
+        let page = client.get_mentions("1000", None, None, 50).await.unwrap();
+
+        assert_eq!(page.data.len(), 3);
+        assert_eq!(page.data[0].author_handle(), "@ada_builds");
+        assert!(page.data[1].author.is_none());
+        assert!(page.data[2].is_retweet());
+        assert_eq!(page.data[2].author_context(), "0 followers");
+    }
+}
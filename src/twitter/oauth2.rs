@@ -0,0 +1,539 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use reqwest::{Client, Request, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use super::auth::{set_authorization, Authenticator, RefreshFuture};
+use super::{percent_encode, TwitterClient, TwitterError};
+
+const AUTHORIZE_URL: &str = "https://x.com/i/oauth2/authorize";
+const TOKEN_URL: &str = "https://api.x.com/2/oauth2/token";
+/// Client-credentials grant for app-only tokens, relative to the API base URL
+const APP_TOKEN_PATH: &str = "/oauth2/token";
+
+/// Everything the bot does, plus `offline.access` for a refresh token
+const SCOPES: &str = "tweet.read tweet.write users.read like.write follows.read follows.write dm.read dm.write media.write offline.access";
+
+/// Needed to upload images; without it posts go out as text only
+pub const MEDIA_SCOPE: &str = "media.write";
+
+/// Refresh this long before the access token's stated expiry
+const EXPIRY_MARGIN_SECS: i64 = 60;
+
+/// How long `authorize` waits for the account owner to finish consent
+const CALLBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Characters allowed in a PKCE code verifier (RFC 7636 unreserved set)
+const VERIFIER_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+
+/// Access and refresh token pair, as persisted between runs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OAuth2Tokens {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    /// Space-separated scopes X granted; missing in files saved before
+    /// scopes were recorded
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+impl OAuth2Tokens {
+    /// Whether X granted `scope`; unknown scopes count as not granted
+    pub fn grants(&self, scope: &str) -> bool {
+        self.scope
+            .as_deref()
+            .is_some_and(|granted| granted.split(' ').any(|g| g == scope))
+    }
+
+    fn expiring(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - Duration::seconds(EXPIRY_MARGIN_SECS) <= now
+    }
+
+    /// Read tokens saved by the `authorize` command
+    pub fn load(path: &Path) -> Result<Self, TwitterError> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            TwitterError::OAuth(format!(
+                "reading {} (run `agentropic-xbot authorize` first): {}",
+                path.display(),
+                e
+            ))
+        })?;
+        serde_json::from_str(&text)
+            .map_err(|e| TwitterError::OAuth(format!("parsing {}: {}", path.display(), e)))
+    }
+
+    /// Write the tokens, readable only by the owner where the OS allows it
+    pub fn save(&self, path: &Path) -> Result<(), TwitterError> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| TwitterError::OAuth(e.to_string()))?;
+        std::fs::write(path, json)
+            .map_err(|e| TwitterError::OAuth(format!("writing {}: {}", path.display(), e)))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| TwitterError::OAuth(format!("securing {}: {}", path.display(), e)))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct AppTokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: i64,
+    scope: Option<String>,
+}
+
+/// PKCE verifier and its S256 challenge
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Self {
+        let mut rng = rand::thread_rng();
+        let verifier: String = (0..64)
+            .map(|_| VERIFIER_CHARS[rng.gen_range(0..VERIFIER_CHARS.len())] as char)
+            .collect();
+        Self::from_verifier(verifier)
+    }
+
+    fn from_verifier(verifier: String) -> Self {
+        let challenge =
+            general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Self {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// OAuth 2.0 app registration from the X developer portal
+#[derive(Debug, Clone)]
+pub struct OAuth2App {
+    client_id: String,
+    /// Only confidential clients have one; public clients rely on PKCE alone
+    client_secret: Option<String>,
+    redirect_uri: String,
+    token_url: String,
+}
+
+impl OAuth2App {
+    pub fn new(client_id: String, client_secret: Option<String>, redirect_uri: String) -> Self {
+        Self {
+            client_id,
+            client_secret,
+            redirect_uri,
+            token_url: TOKEN_URL.to_string(),
+        }
+    }
+
+    /// Exchange codes and refresh tokens at `token_url` instead of X
+    pub fn with_token_url(mut self, token_url: &str) -> Self {
+        self.token_url = token_url.to_string();
+        self
+    }
+
+    /// Page the account owner opens to grant access
+    pub fn authorize_url(&self, state: &str, pkce: &Pkce) -> String {
+        let params = BTreeMap::from([
+            ("client_id", self.client_id.as_str()),
+            ("code_challenge", pkce.challenge.as_str()),
+            ("code_challenge_method", "S256"),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", SCOPES),
+            ("state", state),
+        ]);
+        let query = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, percent_encode(v)))
+            .collect::<Vec<_>>()
+            .join("&");
+        format!("{}?{}", AUTHORIZE_URL, query)
+    }
+
+    /// One-time interactive authorization: print the consent URL, wait for
+    /// X to redirect back to the local callback, then trade the code for tokens.
+    pub async fn authorize(&self, http: &Client) -> Result<OAuth2Tokens, TwitterError> {
+        let redirect = Url::parse(&self.redirect_uri)
+            .map_err(|e| TwitterError::OAuth(format!("invalid redirect URI: {}", e)))?;
+        let host = redirect.host_str().unwrap_or("127.0.0.1").to_string();
+        let port = redirect.port_or_known_default().unwrap_or(80);
+        let listener = TcpListener::bind((host.as_str(), port))
+            .await
+            .map_err(|e| TwitterError::OAuth(format!("binding {}:{}: {}", host, port, e)))?;
+
+        let pkce = Pkce::generate();
+        let state = Pkce::generate().verifier;
+        info!(
+            "Open this URL in a browser signed in as the bot account: {}",
+            self.authorize_url(&state, &pkce)
+        );
+        info!(
+            "Waiting up to {:?} for the OAuth 2.0 callback on {}",
+            CALLBACK_TIMEOUT, self.redirect_uri
+        );
+
+        let code = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_code(&listener, redirect.path(), &state))
+            .await
+            .map_err(|_| TwitterError::OAuth("timed out waiting for the callback".to_string()))??;
+
+        self.token_request(
+            http,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", &code),
+                ("redirect_uri", &self.redirect_uri),
+                ("code_verifier", &pkce.verifier),
+            ],
+        )
+        .await
+    }
+
+    /// POST to the token endpoint and turn the reply into tokens
+    async fn token_request(
+        &self,
+        http: &Client,
+        form: &[(&str, &str)],
+    ) -> Result<OAuth2Tokens, TwitterError> {
+        let mut fields = form.to_vec();
+        fields.push(("client_id", self.client_id.as_str()));
+
+        let mut request = http.post(&self.token_url).form(&fields);
+        if let Some(secret) = &self.client_secret {
+            request = request.basic_auth(&self.client_id, Some(secret));
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(TwitterError::from_response(response).await);
+        }
+
+        let token: TokenResponse = response.json().await?;
+        Ok(OAuth2Tokens {
+            access_token: token.access_token,
+            refresh_token: token.refresh_token,
+            expires_at: Utc::now() + Duration::seconds(token.expires_in),
+            scope: token.scope,
+        })
+    }
+}
+
+/// Accept connections on the callback listener until one carries the
+/// authorization code for `state`
+async fn wait_for_code(
+    listener: &TcpListener,
+    callback_path: &str,
+    state: &str,
+) -> Result<String, TwitterError> {
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| TwitterError::OAuth(e.to_string()))?;
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader
+            .read_line(&mut request_line)
+            .await
+            .map_err(|e| TwitterError::OAuth(e.to_string()))?;
+
+        // Browsers also ask for /favicon.ico and the like
+        let Some(params) = callback_params(&request_line, callback_path) else {
+            let _ = respond(reader.into_inner(), "404 Not Found", "Not found").await;
+            continue;
+        };
+        let result = match (params.get("error"), params.get("code"), params.get("state")) {
+            (Some(error), _, _) => Err(TwitterError::OAuth(format!(
+                "authorization denied: {}",
+                error
+            ))),
+            (None, Some(code), Some(returned)) if returned == state => Ok(code.clone()),
+            (None, Some(_), _) => Err(TwitterError::OAuth(
+                "state mismatch in callback".to_string(),
+            )),
+            (None, None, _) => Err(TwitterError::OAuth("callback carried no code".to_string())),
+        };
+        let page = match &result {
+            Ok(_) => "Authorized. You can close this tab.",
+            Err(_) => "Authorization failed. Check the bot's logs.",
+        };
+        let _ = respond(reader.into_inner(), "200 OK", page).await;
+        return result;
+    }
+}
+
+/// Query parameters of a GET on the callback path, from the HTTP request line
+fn callback_params(request_line: &str, callback_path: &str) -> Option<BTreeMap<String, String>> {
+    let mut parts = request_line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let target = Url::parse(&format!("http://localhost{}", parts.next()?)).ok()?;
+    if target.path() != callback_path {
+        return None;
+    }
+    Some(target.query_pairs().into_owned().collect())
+}
+
+async fn respond(
+    mut stream: tokio::net::TcpStream,
+    status: &str,
+    text: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        text.len(),
+        text
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// OAuth 2.0 user-context bearer tokens, refreshed before expiry and after a 401.
+///
+/// X rotates the refresh token on every use, so each new pair is written
+/// back to `token_path` straight away.
+pub struct OAuth2Session {
+    app: OAuth2App,
+    token_path: PathBuf,
+    tokens: RwLock<OAuth2Tokens>,
+    /// Serializes refreshes so concurrent jobs don't burn the same refresh token
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl OAuth2Session {
+    pub fn new(app: OAuth2App, token_path: impl Into<PathBuf>, tokens: OAuth2Tokens) -> Self {
+        Self {
+            app,
+            token_path: token_path.into(),
+            tokens: RwLock::new(tokens),
+            refresh_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Resume from the tokens saved at `token_path`
+    pub fn load(app: OAuth2App, token_path: impl Into<PathBuf>) -> Result<Self, TwitterError> {
+        let token_path = token_path.into();
+        let tokens = OAuth2Tokens::load(&token_path)?;
+        if !tokens.grants(MEDIA_SCOPE) {
+            warn!(
+                "OAuth 2.0 tokens in {} lack the {} scope: image uploads will fail and posts go out as text only. Run `agentropic-xbot authorize` again to grant it",
+                token_path.display(),
+                MEDIA_SCOPE
+            );
+        }
+        Ok(Self::new(app, token_path, tokens))
+    }
+
+    async fn refresh_tokens(&self, http: &Client, force: bool) -> Result<bool, TwitterError> {
+        let _guard = self.refresh_lock.lock().await;
+        let current = self.tokens.read().unwrap().clone();
+        if !force && !current.expiring(Utc::now()) {
+            return Ok(false);
+        }
+        let Some(refresh_token) = current.refresh_token else {
+            return Ok(false);
+        };
+
+        let mut tokens = self
+            .app
+            .token_request(
+                http,
+                &[
+                    ("grant_type", "refresh_token"),
+                    ("refresh_token", &refresh_token),
+                ],
+            )
+            .await?;
+        // Some grants don't rotate; keep using the old refresh token then
+        if tokens.refresh_token.is_none() {
+            tokens.refresh_token = Some(refresh_token);
+        }
+        if tokens.scope.is_none() {
+            tokens.scope = current.scope;
+        }
+        tokens.save(&self.token_path)?;
+        info!(
+            "OAuth 2.0 access token refreshed, valid until {}",
+            tokens.expires_at
+        );
+        *self.tokens.write().unwrap() = tokens;
+        Ok(true)
+    }
+}
+
+impl Authenticator for OAuth2Session {
+    fn authorize(&self, request: &mut Request) -> Result<(), TwitterError> {
+        let header = format!("Bearer {}", self.tokens.read().unwrap().access_token);
+        set_authorization(request, &header)
+    }
+
+    fn refresh<'a>(&'a self, http: &'a Client, force: bool) -> RefreshFuture<'a> {
+        Box::pin(self.refresh_tokens(http, force))
+    }
+
+    fn secrets(&self) -> Vec<String> {
+        let tokens = self.tokens.read().unwrap();
+        let mut secrets = vec![tokens.access_token.clone()];
+        secrets.extend(tokens.refresh_token.clone());
+        secrets.extend(self.app.client_secret.clone());
+        secrets
+    }
+}
+
+/// OAuth 2.0 app-only bearer token. It acts as the app rather than the bot
+/// account, so it only suits public reads.
+pub struct AppBearer {
+    token: String,
+}
+
+impl AppBearer {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+}
+
+impl Authenticator for AppBearer {
+    fn authorize(&self, request: &mut Request) -> Result<(), TwitterError> {
+        set_authorization(request, &format!("Bearer {}", self.token))
+    }
+
+    fn secrets(&self) -> Vec<String> {
+        vec![self.token.clone()]
+    }
+}
+
+impl TwitterClient {
+    /// Trade the app's consumer key and secret for an app-only bearer token
+    pub async fn fetch_app_bearer(
+        &self,
+        consumer_key: &str,
+        consumer_secret: &str,
+    ) -> Result<AppBearer, TwitterError> {
+        let response = self
+            .client
+            .post(self.api_url(APP_TOKEN_PATH))
+            .basic_auth(consumer_key, Some(consumer_secret))
+            .form(&[("grant_type", "client_credentials")])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(TwitterError::from_response(response).await);
+        }
+
+        let token: AppTokenResponse = response.json().await?;
+        info!("Fetched app-only bearer token");
+        Ok(AppBearer::new(token.access_token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> OAuth2App {
+        OAuth2App::new(
+            "client".to_string(),
+            None,
+            "http://127.0.0.1:8765/callback".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_pkce_challenge_matches_rfc_7636() {
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let generated = Pkce::generate();
+        assert_eq!(generated.verifier.len(), 64);
+        assert!(generated
+            .verifier
+            .bytes()
+            .all(|b| VERIFIER_CHARS.contains(&b)));
+    }
+
+    #[test]
+    fn test_authorize_url_carries_pkce_and_scopes() {
+        let pkce = Pkce::from_verifier("verifier".to_string());
+        let url = app().authorize_url("xyz", &pkce);
+        assert!(url.starts_with(AUTHORIZE_URL));
+        assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains(&format!("code_challenge={}", pkce.challenge)));
+        assert!(url.contains("redirect_uri=http%3A%2F%2F127.0.0.1%3A8765%2Fcallback"));
+        assert!(url.contains("offline.access"));
+        assert!(url.contains("dm.write"));
+        assert!(url.contains("media.write"));
+        assert!(url.contains("state=xyz"));
+    }
+
+    #[test]
+    fn test_callback_params() {
+        let params = callback_params(
+            "GET /callback?state=xyz&code=abc%3D HTTP/1.1\r\n",
+            "/callback",
+        )
+        .unwrap();
+        assert_eq!(params["code"], "abc=");
+        assert_eq!(params["state"], "xyz");
+        assert!(callback_params("GET /favicon.ico HTTP/1.1\r\n", "/callback").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fresh_token_is_not_refreshed() {
+        let session = OAuth2Session::new(
+            app(),
+            "unused.json",
+            OAuth2Tokens {
+                access_token: "abc".to_string(),
+                refresh_token: Some("def".to_string()),
+                expires_at: Utc::now() + Duration::hours(1),
+                scope: Some("tweet.read tweet.write offline.access".to_string()),
+            },
+        );
+
+        assert!(!session.refresh(&Client::new(), false).await.unwrap());
+        let mut request = Client::new()
+            .get("https://api.x.com/2/users/me")
+            .build()
+            .unwrap();
+        session.authorize(&mut request).unwrap();
+        assert_eq!(request.headers()["authorization"], "Bearer abc");
+    }
+
+    #[test]
+    fn test_grants_matches_whole_scopes() {
+        let mut tokens = OAuth2Tokens {
+            access_token: "abc".to_string(),
+            refresh_token: None,
+            expires_at: Utc::now(),
+            scope: Some("tweet.write media.write.extra".to_string()),
+        };
+        assert!(tokens.grants("tweet.write"));
+        assert!(!tokens.grants(MEDIA_SCOPE));
+
+        tokens.scope = None;
+        assert!(!tokens.grants("tweet.write"));
+    }
+}
@@ -0,0 +1,197 @@
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::error::{ApiProblem, TwitterError};
+
+/// Who is asking for a request slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// Scheduled posts; may use every remaining slot
+    Scheduled,
+    /// Replies and other reactive work; must leave the reserve untouched
+    Background,
+}
+
+/// Remaining budget for one endpoint, as last reported by X
+#[derive(Debug, Clone)]
+struct Budget {
+    limit: u32,
+    remaining: u32,
+    reset_at: DateTime<Utc>,
+}
+
+impl Budget {
+    /// Refill the bucket once its window has reset
+    fn refresh(&mut self, now: DateTime<Utc>) {
+        if now >= self.reset_at {
+            self.remaining = self.limit;
+        }
+    }
+}
+
+/// Per-endpoint token buckets fed by `x-rate-limit-*` headers.
+///
+/// One limiter is shared by every job using the client, so the posting and
+/// mention jobs draw from the same budgets.
+pub struct RateLimiter {
+    budgets: Mutex<HashMap<&'static str, Budget>>,
+    max_wait: Duration,
+    reserve: u32,
+}
+
+impl RateLimiter {
+    /// `max_wait`: longest a call will sleep for a window to reset before failing.
+    /// `reserve`: slots per endpoint held back for `Priority::Scheduled` callers.
+    pub fn new(max_wait: Duration, reserve: u32) -> Self {
+        Self {
+            budgets: Mutex::new(HashMap::new()),
+            max_wait,
+            reserve,
+        }
+    }
+
+    /// Take a slot for `endpoint`, waiting for a reset if it is close enough
+    pub async fn acquire(&self, endpoint: &'static str, priority: Priority) -> Result<(), TwitterError> {
+        loop {
+            let reset_at = {
+                let mut budgets = self.budgets.lock().unwrap();
+                let Some(budget) = budgets.get_mut(endpoint) else {
+                    // No headers seen yet for this endpoint
+                    return Ok(());
+                };
+                budget.refresh(Utc::now());
+
+                let floor = match priority {
+                    Priority::Scheduled => 0,
+                    Priority::Background => self.reserve.min(budget.limit.saturating_sub(1)),
+                };
+                if budget.remaining > floor {
+                    budget.remaining -= 1;
+                    debug!("{}: {}/{} requests left", endpoint, budget.remaining, budget.limit);
+                    return Ok(());
+                }
+                budget.reset_at
+            };
+
+            let wait = (reset_at - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+            if wait > self.max_wait {
+                return Err(TwitterError::RateLimited {
+                    reset_at: Some(reset_at),
+                    problem: ApiProblem {
+                        detail: Some(format!("Local budget for {} exhausted", endpoint)),
+                        ..Default::default()
+                    },
+                });
+            }
+            info!("{} budget exhausted, waiting {:?} for reset", endpoint, wait);
+            tokio::time::sleep(wait + Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Update the budget for `endpoint` from response headers
+    pub fn record(&self, endpoint: &'static str, headers: &HeaderMap) {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<i64>().ok())
+        };
+
+        let (Some(limit), Some(remaining), Some(reset)) = (
+            header("x-rate-limit-limit"),
+            header("x-rate-limit-remaining"),
+            header("x-rate-limit-reset"),
+        ) else {
+            return;
+        };
+        let Some(reset_at) = Utc.timestamp_opt(reset, 0).single() else {
+            return;
+        };
+
+        self.budgets.lock().unwrap().insert(
+            endpoint,
+            Budget {
+                limit: limit.max(0) as u32,
+                remaining: remaining.max(0) as u32,
+                reset_at,
+            },
+        );
+    }
+
+    /// Mark `endpoint` as exhausted after a 429
+    pub fn exhaust(&self, endpoint: &'static str, reset_at: DateTime<Utc>) {
+        let mut budgets = self.budgets.lock().unwrap();
+        let budget = budgets.entry(endpoint).or_insert(Budget {
+            limit: 1,
+            remaining: 0,
+            reset_at,
+        });
+        budget.remaining = 0;
+        budget.reset_at = reset_at;
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(60), 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(limit: u32, remaining: u32, reset: DateTime<Utc>) -> HeaderMap {
+        let mut h = HeaderMap::new();
+        h.insert("x-rate-limit-limit", limit.into());
+        h.insert("x-rate-limit-remaining", remaining.into());
+        h.insert("x-rate-limit-reset", reset.timestamp().to_string().parse().unwrap());
+        h
+    }
+
+    #[tokio::test]
+    async fn test_unknown_endpoint_is_unlimited() {
+        let limiter = RateLimiter::default();
+        assert!(limiter.acquire("GET /x", Priority::Background).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_fails_fast() {
+        let limiter = RateLimiter::new(Duration::from_secs(1), 0);
+        let reset = Utc::now() + chrono::Duration::minutes(15);
+        limiter.record("POST /2/tweets", &headers(50, 1, reset));
+
+        assert!(limiter.acquire("POST /2/tweets", Priority::Scheduled).await.is_ok());
+        let err = limiter
+            .acquire("POST /2/tweets", Priority::Scheduled)
+            .await
+            .unwrap_err();
+        assert_eq!(err.rate_limit_reset().unwrap().timestamp(), reset.timestamp());
+    }
+
+    #[tokio::test]
+    async fn test_background_leaves_reserve() {
+        let limiter = RateLimiter::new(Duration::ZERO, 1);
+        let reset = Utc::now() + chrono::Duration::minutes(15);
+        limiter.record("POST /2/tweets", &headers(50, 2, reset));
+
+        assert!(limiter.acquire("POST /2/tweets", Priority::Background).await.is_ok());
+        assert!(limiter.acquire("POST /2/tweets", Priority::Background).await.is_err());
+        assert!(limiter.acquire("POST /2/tweets", Priority::Scheduled).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_budget_refills_after_reset() {
+        let limiter = RateLimiter::new(Duration::ZERO, 0);
+        let reset = Utc::now() - chrono::Duration::seconds(1);
+        limiter.record("GET /2/users/:id/mentions", &headers(10, 0, reset));
+        assert!(limiter
+            .acquire("GET /2/users/:id/mentions", Priority::Background)
+            .await
+            .is_ok());
+    }
+}
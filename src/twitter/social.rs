@@ -0,0 +1,201 @@
+use super::dm::{DmEventsResponse, SendDmResponse};
+use super::engagement::{FollowResponse, LikeResponse};
+use super::followers::FollowersResponse;
+use super::metrics::TweetsLookupResponse;
+use super::{AuthenticatedUser, MentionsResponse, Poll, TweetResponse, TwitterClient, TwitterError};
+
+/// What the posting and mention jobs need from a social network.
+///
+/// `TwitterClient` is the real implementation; `FakeClient` stands in for it
+/// in tests.
+pub trait SocialClient {
+    async fn post_tweet(&self, text: &str, media_ids: &[String]) -> Result<TweetResponse, TwitterError>;
+
+    async fn reply_to_tweet(
+        &self,
+        tweet_id: &str,
+        text: &str,
+        media_ids: &[String],
+    ) -> Result<TweetResponse, TwitterError>;
+
+    async fn post_thread(&self, parts: &[String]) -> Result<Vec<String>, TwitterError>;
+
+    async fn reply_with_thread(&self, tweet_id: &str, parts: &[String]) -> Result<Vec<String>, TwitterError>;
+
+    async fn resume_thread(
+        &self,
+        parts: &[String],
+        in_reply_to: Option<&str>,
+        posted: Vec<String>,
+    ) -> Result<Vec<String>, TwitterError>;
+
+    async fn post_poll(
+        &self,
+        text: &str,
+        options: &[String],
+        duration_minutes: u32,
+    ) -> Result<TweetResponse, TwitterError>;
+
+    async fn get_poll(&self, tweet_id: &str) -> Result<Option<Poll>, TwitterError>;
+
+    async fn upload_media(
+        &self,
+        bytes: &[u8],
+        media_type: &str,
+        alt_text: Option<&str>,
+    ) -> Result<String, TwitterError>;
+
+    async fn get_mentions(
+        &self,
+        user_id: &str,
+        since_id: Option<&str>,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError>;
+
+    async fn search_recent(
+        &self,
+        query: &str,
+        since_id: Option<&str>,
+        max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError>;
+
+    async fn like(&self, user_id: &str, tweet_id: &str) -> Result<LikeResponse, TwitterError>;
+
+    async fn quote_tweet(&self, tweet_id: &str, text: &str) -> Result<TweetResponse, TwitterError>;
+
+    async fn verify_credentials(&self) -> Result<AuthenticatedUser, TwitterError>;
+
+    async fn get_dm_events(
+        &self,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<DmEventsResponse, TwitterError>;
+
+    async fn send_dm(&self, participant_id: &str, text: &str) -> Result<SendDmResponse, TwitterError>;
+
+    async fn get_followers(
+        &self,
+        user_id: &str,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<FollowersResponse, TwitterError>;
+
+    async fn follow(&self, user_id: &str, target_user_id: &str) -> Result<FollowResponse, TwitterError>;
+
+    async fn get_tweet_metrics(&self, ids: &[String]) -> Result<TweetsLookupResponse, TwitterError>;
+}
+
+impl SocialClient for TwitterClient {
+    async fn post_tweet(&self, text: &str, media_ids: &[String]) -> Result<TweetResponse, TwitterError> {
+        TwitterClient::post_tweet(self, text, media_ids).await
+    }
+
+    async fn reply_to_tweet(
+        &self,
+        tweet_id: &str,
+        text: &str,
+        media_ids: &[String],
+    ) -> Result<TweetResponse, TwitterError> {
+        TwitterClient::reply_to_tweet(self, tweet_id, text, media_ids).await
+    }
+
+    async fn post_thread(&self, parts: &[String]) -> Result<Vec<String>, TwitterError> {
+        TwitterClient::post_thread(self, parts).await
+    }
+
+    async fn reply_with_thread(&self, tweet_id: &str, parts: &[String]) -> Result<Vec<String>, TwitterError> {
+        TwitterClient::reply_with_thread(self, tweet_id, parts).await
+    }
+
+    async fn resume_thread(
+        &self,
+        parts: &[String],
+        in_reply_to: Option<&str>,
+        posted: Vec<String>,
+    ) -> Result<Vec<String>, TwitterError> {
+        TwitterClient::resume_thread(self, parts, in_reply_to, posted).await
+    }
+
+    async fn post_poll(
+        &self,
+        text: &str,
+        options: &[String],
+        duration_minutes: u32,
+    ) -> Result<TweetResponse, TwitterError> {
+        TwitterClient::post_poll(self, text, options, duration_minutes).await
+    }
+
+    async fn get_poll(&self, tweet_id: &str) -> Result<Option<Poll>, TwitterError> {
+        TwitterClient::get_poll(self, tweet_id).await
+    }
+
+    async fn upload_media(
+        &self,
+        bytes: &[u8],
+        media_type: &str,
+        alt_text: Option<&str>,
+    ) -> Result<String, TwitterError> {
+        TwitterClient::upload_media(self, bytes, media_type, alt_text).await
+    }
+
+    async fn get_mentions(
+        &self,
+        user_id: &str,
+        since_id: Option<&str>,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError> {
+        TwitterClient::get_mentions(self, user_id, since_id, pagination_token, max_results).await
+    }
+
+    async fn search_recent(
+        &self,
+        query: &str,
+        since_id: Option<&str>,
+        max_results: u32,
+    ) -> Result<MentionsResponse, TwitterError> {
+        TwitterClient::search_recent(self, query, since_id, max_results).await
+    }
+
+    async fn like(&self, user_id: &str, tweet_id: &str) -> Result<LikeResponse, TwitterError> {
+        TwitterClient::like(self, user_id, tweet_id).await
+    }
+
+    async fn quote_tweet(&self, tweet_id: &str, text: &str) -> Result<TweetResponse, TwitterError> {
+        TwitterClient::quote_tweet(self, tweet_id, text).await
+    }
+
+    async fn verify_credentials(&self) -> Result<AuthenticatedUser, TwitterError> {
+        TwitterClient::verify_credentials(self).await
+    }
+
+    async fn get_dm_events(
+        &self,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<DmEventsResponse, TwitterError> {
+        TwitterClient::get_dm_events(self, pagination_token, max_results).await
+    }
+
+    async fn send_dm(&self, participant_id: &str, text: &str) -> Result<SendDmResponse, TwitterError> {
+        TwitterClient::send_dm(self, participant_id, text).await
+    }
+
+    async fn get_followers(
+        &self,
+        user_id: &str,
+        pagination_token: Option<&str>,
+        max_results: u32,
+    ) -> Result<FollowersResponse, TwitterError> {
+        TwitterClient::get_followers(self, user_id, pagination_token, max_results).await
+    }
+
+    async fn follow(&self, user_id: &str, target_user_id: &str) -> Result<FollowResponse, TwitterError> {
+        TwitterClient::follow(self, user_id, target_user_id).await
+    }
+
+    async fn get_tweet_metrics(&self, ids: &[String]) -> Result<TweetsLookupResponse, TwitterError> {
+        TwitterClient::get_tweet_metrics(self, ids).await
+    }
+}
@@ -0,0 +1,299 @@
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc::Sender;
+use tracing::{info, warn};
+
+use super::{
+    MentionData, MentionsIncludes, MentionsMeta, MentionsResponse, Priority, TwitterClient,
+    TwitterError, FILTERED_STREAM, MENTION_TWEET_FIELDS, MENTION_USER_FIELDS, STREAM_RULES,
+    UPDATE_STREAM_RULES,
+};
+
+/// X sends a keep-alive newline every 20 seconds; silence past this means a stalled connection
+const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tag marking the rule this bot owns, so rules added by hand are left alone
+const MENTION_RULE_TAG: &str = "agentropic-xbot mentions";
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StreamRule {
+    pub id: String,
+    pub value: String,
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RulesResponse {
+    #[serde(default)]
+    data: Vec<StreamRule>,
+    /// Rules X refused, e.g. invalid syntax; duplicates are reported here too
+    #[serde(default)]
+    errors: Vec<RuleError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RuleError {
+    title: String,
+    value: Option<String>,
+    details: Option<Vec<String>>,
+}
+
+#[derive(Serialize)]
+struct NewRule<'a> {
+    value: &'a str,
+    tag: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum RulesUpdate<'a> {
+    Add(Vec<NewRule<'a>>),
+    Delete { ids: &'a [String] },
+}
+
+/// One tweet delivered on the filtered stream
+#[derive(Deserialize, Debug)]
+struct StreamEvent {
+    data: MentionData,
+    #[serde(default)]
+    includes: MentionsIncludes,
+}
+
+impl From<StreamEvent> for MentionsResponse {
+    fn from(event: StreamEvent) -> Self {
+        let meta = MentionsMeta {
+            newest_id: Some(event.data.id.clone()),
+            oldest_id: Some(event.data.id.clone()),
+            result_count: 1,
+            next_token: None,
+        };
+        let mut mentions = MentionsResponse {
+            data: vec![event.data],
+            includes: event.includes,
+            meta: Some(meta),
+        };
+        mentions.resolve_authors();
+        mentions
+    }
+}
+
+/// Wait before the `attempt`th reconnect (from 0), following X's guidance:
+/// linear for network trouble, exponential for HTTP errors and slower still
+/// after a 429
+fn reconnect_delay(error: &TwitterError, attempt: u32) -> Duration {
+    let doubling = 2u64.saturating_pow(attempt.min(10));
+    match error {
+        TwitterError::Http(_) | TwitterError::Stream(_) => {
+            Duration::from_millis((250 * (attempt as u64 + 1)).min(16_000))
+        }
+        TwitterError::RateLimited { .. } => Duration::from_secs((60 * doubling).min(960)),
+        _ => Duration::from_secs((5 * doubling).min(320)),
+    }
+}
+
+impl TwitterClient {
+    /// Rules currently filtering the stream
+    pub async fn stream_rules(&self) -> Result<Vec<StreamRule>, TwitterError> {
+        let url = self.api_url("/2/tweets/search/stream/rules");
+
+        let request = self.client.get(&url);
+        let response = self
+            .send(STREAM_RULES, Priority::Background, request)
+            .await?;
+
+        let rules: RulesResponse = response.json().await?;
+        Ok(rules.data)
+    }
+
+    async fn update_stream_rules(&self, update: RulesUpdate<'_>) -> Result<(), TwitterError> {
+        let url = self.api_url("/2/tweets/search/stream/rules");
+
+        let request = self.client.post(&url).json(&update);
+        let response = self
+            .send(UPDATE_STREAM_RULES, Priority::Background, request)
+            .await?;
+
+        let result: RulesResponse = response.json().await?;
+        match result.errors.iter().find(|e| e.title != "DuplicateRule") {
+            Some(error) => Err(TwitterError::Stream(format!(
+                "rule {:?} rejected: {} {}",
+                error.value.as_deref().unwrap_or_default(),
+                error.title,
+                error.details.as_deref().unwrap_or_default().join("; ")
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Make the bot's tagged rule match only mentions of `username`
+    pub async fn sync_mention_rule(&self, username: &str) -> Result<(), TwitterError> {
+        let value = format!("@{} -from:{} -is:retweet", username, username);
+        let rules = self.stream_rules().await?;
+
+        let stale: Vec<String> = rules
+            .iter()
+            .filter(|r| r.tag.as_deref() == Some(MENTION_RULE_TAG) && r.value != value)
+            .map(|r| r.id.clone())
+            .collect();
+        if !stale.is_empty() {
+            info!("Deleting {} outdated stream rule(s)", stale.len());
+            self.update_stream_rules(RulesUpdate::Delete { ids: &stale })
+                .await?;
+        }
+
+        if !rules.iter().any(|r| r.value == value) {
+            info!("Adding stream rule: {}", value);
+            let rule = NewRule {
+                value: &value,
+                tag: MENTION_RULE_TAG,
+            };
+            self.update_stream_rules(RulesUpdate::Add(vec![rule]))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn connect_stream(&self) -> Result<Response, TwitterError> {
+        let url = self.api_url("/2/tweets/search/stream");
+
+        let request = self.client.get(&url).query(&[
+            ("tweet.fields", MENTION_TWEET_FIELDS),
+            ("expansions", "author_id"),
+            ("user.fields", MENTION_USER_FIELDS),
+        ]);
+        self.send(FILTERED_STREAM, Priority::Background, request)
+            .await
+    }
+
+    /// Deliver mentions of `username` from the filtered stream to `sender`,
+    /// one tweet per batch, reconnecting with backoff whenever the connection
+    /// drops or stalls.
+    ///
+    /// Returns once `sender`'s receiver is gone, or with the error when X
+    /// refuses the credentials outright.
+    pub async fn stream_mentions(
+        &self,
+        username: &str,
+        sender: Sender<MentionsResponse>,
+    ) -> Result<(), TwitterError> {
+        let mut rule_synced = false;
+        let mut attempt = 0;
+        loop {
+            // A failed rule sync is retried with the same backoff as a failed connect
+            let synced = match rule_synced {
+                true => Ok(()),
+                false => self.sync_mention_rule(username).await,
+            };
+            let connected = match synced {
+                Ok(()) => {
+                    rule_synced = true;
+                    self.connect_stream().await
+                }
+                Err(e) => Err(e),
+            };
+            let error = match connected {
+                Ok(response) => {
+                    info!("Connected to the filtered stream");
+                    attempt = 0;
+                    match read_stream(response, &sender).await {
+                        Ok(()) => return Ok(()),
+                        Err(e) => e,
+                    }
+                }
+                Err(e @ (TwitterError::Unauthorized { .. } | TwitterError::Forbidden { .. })) => {
+                    return Err(e)
+                }
+                Err(e) => e,
+            };
+
+            let delay = reconnect_delay(&error, attempt);
+            warn!(
+                "Filtered stream disconnected ({}), reconnecting in {:?}",
+                error, delay
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Forward every tweet on an open stream until it fails. `Ok` means the
+/// receiver was dropped and nobody is listening any more.
+async fn read_stream(
+    mut response: Response,
+    sender: &Sender<MentionsResponse>,
+) -> Result<(), TwitterError> {
+    let mut buffer = Vec::new();
+    loop {
+        let chunk = tokio::time::timeout(KEEP_ALIVE_TIMEOUT, response.chunk())
+            .await
+            .map_err(|_| {
+                TwitterError::Stream(format!("no keep-alive for {:?}", KEEP_ALIVE_TIMEOUT))
+            })??
+            .ok_or_else(|| TwitterError::Stream("connection closed by X".to_string()))?;
+        buffer.extend_from_slice(&chunk);
+
+        while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            // Blank lines are keep-alives
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<StreamEvent>(&line) {
+                Ok(event) => {
+                    if sender.send(event.into()).await.is_err() {
+                        return Ok(());
+                    }
+                }
+                Err(e) => warn!(
+                    "Skipping unreadable stream message ({}): {}",
+                    e,
+                    line.trim()
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitter::error::ApiProblem;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_stream_event_becomes_single_mention_batch() {
+        let line = r#"{"data":{"id":"1700","text":"@agentropic hi","author_id":"7"},
+            "includes":{"users":[{"id":"7","username":"alice"}]},
+            "matching_rules":[{"id":"1","tag":"agentropic-xbot mentions"}]}"#;
+        let event: StreamEvent = serde_json::from_str(line).unwrap();
+
+        let batch = MentionsResponse::from(event);
+        assert_eq!(batch.data.len(), 1);
+        assert_eq!(batch.data[0].author_handle(), "@alice");
+        assert_eq!(batch.meta.unwrap().newest_id.as_deref(), Some("1700"));
+    }
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let network = TwitterError::Stream("stalled".to_string());
+        assert_eq!(reconnect_delay(&network, 0), Duration::from_millis(250));
+        assert_eq!(reconnect_delay(&network, 200), Duration::from_secs(16));
+
+        let http = TwitterError::Server {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            problem: ApiProblem::default(),
+        };
+        assert_eq!(reconnect_delay(&http, 0), Duration::from_secs(5));
+        assert_eq!(reconnect_delay(&http, 3), Duration::from_secs(40));
+        assert_eq!(reconnect_delay(&http, 30), Duration::from_secs(320));
+
+        let limited = TwitterError::RateLimited {
+            reset_at: None,
+            problem: ApiProblem::default(),
+        };
+        assert_eq!(reconnect_delay(&limited, 1), Duration::from_secs(120));
+    }
+}
@@ -0,0 +1,442 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::Sender;
+use tracing::{debug, info, warn};
+
+use crate::twitter::{
+    MentionAuthor, MentionData, MentionsIncludes, MentionsMeta, MentionsResponse, ReferenceKind,
+    ReferencedTweet, UserMetrics,
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Largest request body accepted; activity payloads are a few KB
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Longest request or header line accepted
+const MAX_LINE_BYTES: u64 = 8 * 1024;
+
+/// Most header lines read from one request
+const MAX_HEADERS: usize = 64;
+
+/// A connection that hasn't finished its request by then is dropped
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Account Activity payload; only the events the bot reacts to are read
+#[derive(Deserialize, Debug)]
+struct ActivityEvent {
+    for_user_id: String,
+    #[serde(default)]
+    tweet_create_events: Vec<ActivityTweet>,
+}
+
+/// v1.1 tweet object as delivered in `tweet_create_events`
+#[derive(Deserialize, Debug)]
+struct ActivityTweet {
+    id_str: String,
+    text: String,
+    /// Holds the untruncated text of tweets over 140 characters
+    extended_tweet: Option<ExtendedTweet>,
+    created_at: String,
+    lang: Option<String>,
+    user: ActivityUser,
+    in_reply_to_status_id_str: Option<String>,
+    in_reply_to_user_id_str: Option<String>,
+    quoted_status_id_str: Option<String>,
+    retweeted_status: Option<RetweetedStatus>,
+    #[serde(default)]
+    entities: Entities,
+}
+
+#[derive(Deserialize, Debug)]
+struct ExtendedTweet {
+    full_text: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ActivityUser {
+    id_str: String,
+    screen_name: String,
+    #[serde(default)]
+    verified: bool,
+    #[serde(default)]
+    followers_count: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct RetweetedStatus {
+    id_str: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct Entities {
+    #[serde(default)]
+    user_mentions: Vec<UserMention>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserMention {
+    id_str: String,
+}
+
+impl ActivityTweet {
+    fn mentions(&self, user_id: &str) -> bool {
+        self.entities
+            .user_mentions
+            .iter()
+            .any(|m| m.id_str == user_id)
+    }
+
+    /// The same tweet in the v2 shape the reply pipeline reads
+    fn into_mention(self) -> MentionData {
+        let references = [
+            (ReferenceKind::RepliedTo, self.in_reply_to_status_id_str),
+            (ReferenceKind::Quoted, self.quoted_status_id_str),
+            (
+                ReferenceKind::Retweeted,
+                self.retweeted_status.map(|r| r.id_str),
+            ),
+        ];
+        let referenced_tweets = references
+            .into_iter()
+            .filter_map(|(kind, id)| id.map(|id| ReferencedTweet { kind, id }))
+            .collect();
+
+        MentionData {
+            id: self.id_str,
+            text: self
+                .extended_tweet
+                .map(|e| e.full_text)
+                .unwrap_or(self.text),
+            author_id: self.user.id_str.clone(),
+            created_at: DateTime::parse_from_str(&self.created_at, "%a %b %d %H:%M:%S %z %Y")
+                .ok()
+                .map(|t| t.with_timezone(&Utc)),
+            conversation_id: None,
+            lang: self.lang,
+            referenced_tweets,
+            in_reply_to_user_id: self.in_reply_to_user_id_str,
+            author: Some(MentionAuthor {
+                id: self.user.id_str,
+                username: self.user.screen_name,
+                verified: self.user.verified,
+                public_metrics: UserMetrics {
+                    followers_count: self.user.followers_count,
+                },
+            }),
+        }
+    }
+}
+
+/// Tweets in `event` that mention the subscribed user, newest first, as one batch
+fn mention_batch(event: ActivityEvent) -> Option<MentionsResponse> {
+    let user_id = event.for_user_id;
+    let mut data: Vec<MentionData> = event
+        .tweet_create_events
+        .into_iter()
+        .filter(|t| t.mentions(&user_id))
+        .map(ActivityTweet::into_mention)
+        .collect();
+    if data.is_empty() {
+        return None;
+    }
+    data.sort_by_key(|m| std::cmp::Reverse(m.id.parse::<u64>().unwrap_or(0)));
+
+    let meta = MentionsMeta {
+        newest_id: data.first().map(|m| m.id.clone()),
+        oldest_id: data.last().map(|m| m.id.clone()),
+        result_count: data.len() as u64,
+        next_token: None,
+    };
+    Some(MentionsResponse {
+        data,
+        includes: MentionsIncludes::default(),
+        meta: Some(meta),
+    })
+}
+
+/// `sha256=<base64 HMAC-SHA256 of message>`, keyed with the consumer secret
+fn sign(consumer_secret: &str, message: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(consumer_secret.as_bytes())
+        .expect("HMAC accepts any key length");
+    mac.update(message);
+    format!(
+        "sha256={}",
+        general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Check the `x-twitter-webhooks-signature` header against the body in constant time
+fn signature_valid(consumer_secret: &str, body: &[u8], header: Option<&str>) -> bool {
+    let Some(signature) = header
+        .and_then(|h| h.strip_prefix("sha256="))
+        .and_then(|s| general_purpose::STANDARD.decode(s).ok())
+    else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(consumer_secret.as_bytes())
+        .expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Receive Account Activity webhooks on `listener`: answer X's CRC challenges
+/// and forward mentions from signed `tweet_create_events` deliveries to
+/// `sender`. Runs until the receiver is dropped.
+///
+/// X only delivers to HTTPS URLs, so this sits behind a TLS-terminating proxy.
+pub async fn serve(
+    listener: TcpListener,
+    consumer_secret: String,
+    sender: Sender<MentionsResponse>,
+) -> std::io::Result<()> {
+    info!("Webhook receiver listening on {}", listener.local_addr()?);
+    while !sender.is_closed() {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                // Usually out of file descriptors; back off instead of spinning
+                warn!("Failed to accept webhook connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let consumer_secret = consumer_secret.clone();
+        let sender = sender.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(CONNECTION_TIMEOUT, handle(stream, &consumer_secret, &sender)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => debug!("Webhook connection from {} failed: {}", peer, e),
+                Err(_) => debug!("Webhook connection from {} timed out", peer),
+            }
+        });
+    }
+    Ok(())
+}
+
+async fn handle(
+    stream: TcpStream,
+    consumer_secret: &str,
+    sender: &Sender<MentionsResponse>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let request_line = read_line(&mut reader).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = Url::parse(&format!("http://localhost{}", parts.next().unwrap_or("/")))
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    let mut headers = HashMap::new();
+    for count in 0.. {
+        let line = read_line(&mut reader).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if count == MAX_HEADERS {
+            return respond(reader.into_inner(), "431 Request Header Fields Too Large", "{}").await;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return respond(reader.into_inner(), "413 Payload Too Large", "{}").await;
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await?;
+    let stream = reader.into_inner();
+
+    match method.as_str() {
+        "GET" => {
+            let Some((_, token)) = target.query_pairs().find(|(k, _)| k == "crc_token") else {
+                return respond(stream, "400 Bad Request", "{}").await;
+            };
+            info!("Answering webhook CRC challenge");
+            let reply = json!({ "response_token": sign(consumer_secret, token.as_bytes()) });
+            respond(stream, "200 OK", &reply.to_string()).await
+        }
+        "POST" => {
+            let signature = headers
+                .get("x-twitter-webhooks-signature")
+                .map(String::as_str);
+            if !signature_valid(consumer_secret, &body, signature) {
+                warn!("Rejected webhook delivery with a bad signature");
+                return respond(stream, "401 Unauthorized", "{}").await;
+            }
+            let event: ActivityEvent = match serde_json::from_slice(&body) {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!("Unreadable webhook delivery: {}", e);
+                    return respond(stream, "400 Bad Request", "{}").await;
+                }
+            };
+            // Answer before the reply pipeline runs; X expects a quick 200
+            respond(stream, "200 OK", "{}").await?;
+            if let Some(batch) = mention_batch(event) {
+                let _ = sender.send(batch).await;
+            }
+            Ok(())
+        }
+        _ => respond(stream, "405 Method Not Allowed", "{}").await,
+    }
+}
+
+/// One CRLF-terminated line, refusing lines over `MAX_LINE_BYTES`
+async fn read_line(reader: &mut BufReader<TcpStream>) -> std::io::Result<String> {
+    let mut line = String::new();
+    (&mut *reader).take(MAX_LINE_BYTES).read_line(&mut line).await?;
+    if line.len() as u64 == MAX_LINE_BYTES && !line.ends_with('\n') {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "request line too long",
+        ));
+    }
+    Ok(line)
+}
+
+async fn respond(mut stream: TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    const SECRET: &str = "mock-consumer-secret";
+
+    fn activity(for_user_id: &str) -> serde_json::Value {
+        json!({
+            "for_user_id": for_user_id,
+            "tweet_create_events": [
+                {
+                    "id_str": "1800",
+                    "text": "@agentropic what patterns",
+                    "extended_tweet": { "full_text": "@agentropic what patterns do you support?" },
+                    "created_at": "Wed Oct 10 20:19:24 +0000 2018",
+                    "lang": "en",
+                    "user": { "id_str": "7", "screen_name": "alice", "followers_count": 12 },
+                    "in_reply_to_status_id_str": "1799",
+                    "in_reply_to_user_id_str": "1000",
+                    "entities": { "user_mentions": [{ "id_str": "1000" }] }
+                },
+                {
+                    "id_str": "1801",
+                    "text": "unrelated post by someone we follow",
+                    "created_at": "Wed Oct 10 20:20:00 +0000 2018",
+                    "user": { "id_str": "8", "screen_name": "bob" }
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_crc_response_token() {
+        // Computed independently with Python's hmac module
+        assert_eq!(
+            sign(SECRET, b"challenge-123"),
+            "sha256=TkEQVpYP2UGzpHYyjuKv4gGDsla/8A0e9MA+2VSVKlM="
+        );
+    }
+
+    #[test]
+    fn test_keeps_only_tweets_mentioning_the_user() {
+        let event: ActivityEvent = serde_json::from_value(activity("1000")).unwrap();
+        let batch = mention_batch(event).unwrap();
+
+        assert_eq!(batch.data.len(), 1);
+        let mention = &batch.data[0];
+        assert_eq!(mention.text, "@agentropic what patterns do you support?");
+        assert_eq!(mention.author_handle(), "@alice");
+        assert_eq!(mention.referenced_tweets[0].kind, ReferenceKind::RepliedTo);
+        assert_eq!(mention.referenced_tweets[0].id, "1799");
+        assert_eq!(mention.in_reply_to_user_id.as_deref(), Some("1000"));
+        assert_eq!(
+            mention.created_at.unwrap().to_rfc3339(),
+            "2018-10-10T20:19:24+00:00"
+        );
+
+        let event: ActivityEvent = serde_json::from_value(activity("2000")).unwrap();
+        assert!(mention_batch(event).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_serves_crc_and_forwards_signed_deliveries() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+        let (sender, mut receiver) = mpsc::channel(4);
+        tokio::spawn(serve(listener, SECRET.to_string(), sender));
+        let http = reqwest::Client::new();
+
+        let crc: serde_json::Value = http
+            .get(&url)
+            .query(&[("crc_token", "challenge-123")])
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(crc["response_token"], sign(SECRET, b"challenge-123"));
+
+        let body = activity("1000").to_string();
+        let forged = http.post(&url).body(body.clone()).send().await.unwrap();
+        assert_eq!(forged.status(), 401);
+
+        let signed = http
+            .post(&url)
+            .header(
+                "x-twitter-webhooks-signature",
+                sign(SECRET, body.as_bytes()),
+            )
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(signed.status(), 200);
+        let batch = receiver.recv().await.unwrap();
+        assert_eq!(batch.data[0].id, "1800");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_oversized_headers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+        let (sender, _receiver) = mpsc::channel(4);
+        tokio::spawn(serve(listener, SECRET.to_string(), sender));
+        let http = reqwest::Client::new();
+
+        let mut request = http.get(&url).query(&[("crc_token", "challenge-123")]);
+        for i in 0..MAX_HEADERS {
+            request = request.header(format!("x-padding-{}", i), "1");
+        }
+        assert_eq!(request.send().await.unwrap().status(), 431);
+
+        let long_line = "a".repeat(MAX_LINE_BYTES as usize);
+        let dropped = http.get(&url).header("x-padding", long_line).send().await;
+        assert!(dropped.is_err());
+    }
+}